@@ -10,7 +10,7 @@ extern crate curl_sys;
 extern crate openssl_sys;
 extern crate libz_sys as libz;
 
-use libc::{c_int, c_char, c_uint, size_t, c_uchar, c_void};
+use libc::{c_int, c_char, c_uint, size_t, c_uchar, c_void, c_ushort};
 
 pub const GIT_OID_RAWSZ: usize = 20;
 pub const GIT_OID_HEXSZ: usize = GIT_OID_RAWSZ * 2;
@@ -18,6 +18,9 @@ pub const GIT_CLONE_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_STASH_APPLY_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_CHECKOUT_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_MERGE_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_MERGE_FILE_INPUT_VERSION: c_uint = 1;
+pub const GIT_MERGE_FILE_OPTIONS_VERSION: c_uint = 1;
+pub const GIT_WORKTREE_ADD_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_REMOTE_CALLBACKS_VERSION: c_uint = 1;
 pub const GIT_STATUS_OPTIONS_VERSION: c_uint = 1;
 pub const GIT_BLAME_OPTIONS_VERSION: c_uint = 1;
@@ -54,9 +57,11 @@ pub enum git_commit {}
 pub enum git_config {}
 pub enum git_config_iterator {}
 pub enum git_index {}
+pub enum git_index_conflict_iterator {}
 pub enum git_object {}
 pub enum git_reference {}
 pub enum git_reference_iterator {}
+pub enum git_refdb {}
 pub enum git_annotated_commit {}
 pub enum git_refspec {}
 pub enum git_remote {}
@@ -82,6 +87,21 @@ pub enum git_describe_result {}
 pub enum git_packbuilder {}
 pub enum git_odb {}
 pub enum git_odb_stream {}
+pub enum git_odb_backend {}
+pub enum git_worktree {}
+pub enum git_transaction {}
+
+#[repr(C)]
+pub struct git_odb_writepack {
+    pub backend: *mut git_odb_backend,
+    pub append: extern fn(*mut git_odb_writepack,
+                          *const c_void,
+                          size_t,
+                          *mut git_transfer_progress) -> c_int,
+    pub commit: extern fn(*mut git_odb_writepack,
+                          *mut git_transfer_progress) -> c_int,
+    pub free: extern fn(*mut git_odb_writepack),
+}
 
 #[repr(C)]
 pub struct git_revspec {
@@ -523,6 +543,15 @@ git_enum! {
     }
 }
 
+git_enum! {
+    pub enum git_diff_flag_t {
+        GIT_DIFF_FLAG_BINARY = 1 << 0,
+        GIT_DIFF_FLAG_NOT_BINARY = 1 << 1,
+        GIT_DIFF_FLAG_VALID_ID = 1 << 2,
+        GIT_DIFF_FLAG_EXISTS = 1 << 3,
+    }
+}
+
 git_enum! {
     pub enum git_delta_t {
         GIT_DELTA_UNMODIFIED,
@@ -627,6 +656,7 @@ git_enum! {
         GIT_FILEMODE_UNREADABLE = 0o000000,
         GIT_FILEMODE_TREE = 0o040000,
         GIT_FILEMODE_BLOB = 0o100644,
+        GIT_FILEMODE_BLOB_GROUP_WRITABLE = 0o100664,
         GIT_FILEMODE_BLOB_EXECUTABLE = 0o100755,
         GIT_FILEMODE_LINK = 0o120000,
         GIT_FILEMODE_COMMIT = 0o160000,
@@ -1029,8 +1059,8 @@ pub struct git_diff_options {
     pub flags: u32,
     pub ignore_submodules: git_submodule_ignore_t,
     pub pathspec: git_strarray,
-    pub notify_cb: git_diff_notify_cb,
-    pub progress_cb: git_diff_progress_cb,
+    pub notify_cb: Option<git_diff_notify_cb>,
+    pub progress_cb: Option<git_diff_progress_cb>,
     pub payload: *mut c_void,
     pub context_lines: u32,
     pub interhunk_lines: u32,
@@ -1216,6 +1246,35 @@ git_enum! {
     }
 }
 
+#[repr(C)]
+pub struct git_merge_file_input {
+    pub version: c_uint,
+    pub ptr: *const c_char,
+    pub size: size_t,
+    pub path: *const c_char,
+    pub mode: c_uint,
+}
+
+#[repr(C)]
+pub struct git_merge_file_options {
+    pub version: c_uint,
+    pub ancestor_label: *const c_char,
+    pub our_label: *const c_char,
+    pub their_label: *const c_char,
+    pub favor: git_merge_file_favor_t,
+    pub flags: git_merge_file_flag_t,
+    pub marker_size: c_ushort,
+}
+
+#[repr(C)]
+pub struct git_merge_file_result {
+    pub automergeable: c_uint,
+    pub path: *const c_char,
+    pub mode: c_uint,
+    pub ptr: *const c_char,
+    pub len: size_t,
+}
+
 git_enum! {
     pub enum git_merge_analysis_t {
         GIT_MERGE_ANALYSIS_NONE = 0,
@@ -1421,6 +1480,67 @@ pub type git_packbuilder_foreach_cb = extern fn(*const c_void, size_t,
 
 pub type git_odb_foreach_cb = extern fn(id: *const git_oid, payload: *mut c_void) -> c_int;
 
+#[repr(C)]
+pub struct git_worktree_add_options {
+    pub version: c_uint,
+    pub lock: c_int,
+}
+
+git_enum! {
+    pub enum git_attr_t {
+        GIT_ATTR_UNSPECIFIED_T = 0,
+        GIT_ATTR_TRUE_T,
+        GIT_ATTR_FALSE_T,
+        GIT_ATTR_VALUE_T,
+    }
+}
+
+git_enum! {
+    pub enum git_libgit2_opt_t {
+        GIT_OPT_GET_MWINDOW_SIZE = 0,
+        GIT_OPT_SET_MWINDOW_SIZE,
+        GIT_OPT_GET_MWINDOW_MAPPED_LIMIT,
+        GIT_OPT_SET_MWINDOW_MAPPED_LIMIT,
+        GIT_OPT_GET_SEARCH_PATH,
+        GIT_OPT_SET_SEARCH_PATH,
+        GIT_OPT_SET_CACHE_OBJECT_LIMIT,
+        GIT_OPT_SET_CACHE_MAX_SIZE,
+        GIT_OPT_ENABLE_CACHING,
+        GIT_OPT_GET_CACHED_MEMORY,
+        GIT_OPT_GET_TEMPLATE_PATH,
+        GIT_OPT_SET_TEMPLATE_PATH,
+        GIT_OPT_SET_SSL_CERT_LOCATIONS,
+        GIT_OPT_SET_USER_AGENT,
+        GIT_OPT_ENABLE_STRICT_OBJECT_CREATION,
+        GIT_OPT_ENABLE_STRICT_SYMBOLIC_REF_CREATION,
+        GIT_OPT_SET_SSL_CIPHERS,
+        GIT_OPT_GET_USER_AGENT,
+        GIT_OPT_ENABLE_OFS_DELTA,
+    }
+}
+
+pub const GIT_ATTR_CHECK_FILE_THEN_INDEX: u32 = 0;
+pub const GIT_ATTR_CHECK_INDEX_THEN_FILE: u32 = 1;
+pub const GIT_ATTR_CHECK_INDEX_ONLY: u32 = 2;
+pub const GIT_ATTR_CHECK_NO_SYSTEM: u32 = 1 << 2;
+
+pub type git_repository_fetchhead_foreach_cb = extern fn(ref_name: *const c_char,
+                                                         remote_url: *const c_char,
+                                                         oid: *const git_oid,
+                                                         is_merge: c_uint,
+                                                         payload: *mut c_void)
+                                                         -> c_int;
+pub type git_repository_mergehead_foreach_cb = extern fn(oid: *const git_oid,
+                                                         payload: *mut c_void)
+                                                         -> c_int;
+
+pub type git_reference_foreach_cb = extern fn(reference: *mut git_reference,
+                                              payload: *mut c_void) -> c_int;
+
+pub type git_attr_foreach_cb = extern fn(name: *const c_char,
+                                         value: *const c_char,
+                                         payload: *mut c_void) -> c_int;
+
 extern {
     // threads
     pub fn git_libgit2_init() -> c_int;
@@ -1445,16 +1565,31 @@ extern {
                                             version: c_uint) -> c_int;
     pub fn git_repository_get_namespace(repo: *mut git_repository)
                                         -> *const c_char;
+    pub fn git_repository_set_namespace(repo: *mut git_repository,
+                                        nmspace: *const c_char) -> c_int;
     pub fn git_repository_head(out: *mut *mut git_reference,
                                repo: *mut git_repository) -> c_int;
+    pub fn git_repository_head_for_worktree(out: *mut *mut git_reference,
+                                            repo: *mut git_repository,
+                                            name: *const c_char) -> c_int;
+    pub fn git_repository_head_detached_for_worktree(repo: *mut git_repository,
+                                                     name: *const c_char)
+                                                     -> c_int;
+    pub fn git_repository_open_from_worktree(out: *mut *mut git_repository,
+                                             wt: *mut git_worktree) -> c_int;
     pub fn git_repository_set_head(repo: *mut git_repository,
                                    refname: *const c_char) -> c_int;
     pub fn git_repository_set_head_detached(repo: *mut git_repository,
                                             commitish: *const git_oid) -> c_int;
+    pub fn git_repository_set_head_detached_from_annotated(
+        repo: *mut git_repository,
+        commitish: *const git_annotated_commit) -> c_int;
     pub fn git_repository_is_bare(repo: *mut git_repository) -> c_int;
     pub fn git_repository_is_empty(repo: *mut git_repository) -> c_int;
     pub fn git_repository_is_shallow(repo: *mut git_repository) -> c_int;
     pub fn git_repository_path(repo: *mut git_repository) -> *const c_char;
+    pub fn git_repository_commondir(repo: *mut git_repository) -> *const c_char;
+    pub fn git_repository_is_worktree(repo: *mut git_repository) -> c_int;
     pub fn git_repository_state(repo: *mut git_repository) -> c_int;
     pub fn git_repository_workdir(repo: *mut git_repository) -> *const c_char;
     pub fn git_repository_set_workdir(repo: *mut git_repository,
@@ -1468,6 +1603,13 @@ extern {
                                  repo: *mut git_repository) -> c_int;
     pub fn git_repository_config_snapshot(out: *mut *mut git_config,
                                           repo: *mut git_repository) -> c_int;
+    pub fn git_repository_set_config(repo: *mut git_repository,
+                                     config: *mut git_config);
+    pub fn git_repository_hashfile(out: *mut git_oid,
+                                   repo: *mut git_repository,
+                                   path: *const c_char,
+                                   otype: git_otype,
+                                   as_path: *const c_char) -> c_int;
     pub fn git_repository_discover(out: *mut git_buf,
                                    start_path: *const c_char,
                                    across_fs: c_int,
@@ -1494,6 +1636,11 @@ extern {
                              repo: *mut git_repository,
                              id: *const git_oid,
                              kind: git_otype) -> c_int;
+    pub fn git_object_lookup_prefix(dest: *mut *mut git_object,
+                                    repo: *mut git_repository,
+                                    id: *const git_oid,
+                                    len: size_t,
+                                    kind: git_otype) -> c_int;
     pub fn git_object_type(obj: *const git_object) -> git_otype;
     pub fn git_object_peel(peeled: *mut *mut git_object,
                            object: *const git_object,
@@ -1624,6 +1771,8 @@ extern {
     pub fn git_signature_default(out: *mut *mut git_signature,
                                  repo: *mut git_repository) -> c_int;
     pub fn git_signature_free(sig: *mut git_signature);
+    pub fn git_signature_from_buffer(out: *mut *mut git_signature,
+                                     buf: *const c_char) -> c_int;
     pub fn git_signature_new(out: *mut *mut git_signature,
                              name: *const c_char,
                              email: *const c_char,
@@ -1669,10 +1818,18 @@ extern {
                              target: *mut git_object,
                              pathspecs: *mut git_strarray) -> c_int;
 
+    // refdb
+    pub fn git_repository_refdb(out: *mut *mut git_refdb,
+                                repo: *mut git_repository) -> c_int;
+    pub fn git_refdb_compress(refdb: *mut git_refdb) -> c_int;
+    pub fn git_refdb_free(refdb: *mut git_refdb);
+
     // reference
     pub fn git_reference_cmp(ref1: *const git_reference,
                              ref2: *const git_reference) -> c_int;
     pub fn git_reference_delete(r: *mut git_reference) -> c_int;
+    pub fn git_reference_remove(repo: *mut git_repository,
+                                name: *const c_char) -> c_int;
     pub fn git_reference_free(r: *mut git_reference);
     pub fn git_reference_is_branch(r: *const git_reference) -> c_int;
     pub fn git_reference_is_note(r: *const git_reference) -> c_int;
@@ -1682,7 +1839,11 @@ extern {
     pub fn git_reference_lookup(out: *mut *mut git_reference,
                                 repo: *mut git_repository,
                                 name: *const c_char) -> c_int;
+    pub fn git_reference_dwim(out: *mut *mut git_reference,
+                              repo: *mut git_repository,
+                              refname: *const c_char) -> c_int;
     pub fn git_reference_name(r: *const git_reference) -> *const c_char;
+    pub fn git_reference_owner(r: *const git_reference) -> *mut git_repository;
     pub fn git_reference_name_to_id(out: *mut git_oid,
                                     repo: *mut git_repository,
                                     name: *const c_char) -> c_int;
@@ -1711,6 +1872,9 @@ extern {
                                            repo: *mut git_repository,
                                            glob: *const c_char) -> c_int;
     pub fn git_reference_iterator_free(iter: *mut git_reference_iterator);
+    pub fn git_reference_foreach(repo: *mut git_repository,
+                                 callback: git_reference_foreach_cb,
+                                 payload: *mut c_void) -> c_int;
     pub fn git_reference_next(out: *mut *mut git_reference,
                               iter: *mut git_reference_iterator) -> c_int;
     pub fn git_reference_next_name(out: *mut *const c_char,
@@ -1929,7 +2093,12 @@ extern {
     pub fn git_commit_lookup(commit: *mut *mut git_commit,
                              repo: *mut git_repository,
                              id: *const git_oid) -> c_int;
+    pub fn git_commit_lookup_prefix(commit: *mut *mut git_commit,
+                                    repo: *mut git_repository,
+                                    id: *const git_oid,
+                                    len: size_t) -> c_int;
     pub fn git_commit_message(commit: *const git_commit) -> *const c_char;
+    pub fn git_commit_owner(commit: *const git_commit) -> *mut git_repository;
     pub fn git_commit_message_encoding(commit: *const git_commit) -> *const c_char;
     pub fn git_commit_message_raw(commit: *const git_commit) -> *const c_char;
     pub fn git_commit_nth_gen_ancestor(commit: *mut *mut git_commit,
@@ -2002,6 +2171,12 @@ extern {
                                    upstream_name: *const c_char) -> c_int;
     pub fn git_branch_upstream(out: *mut *mut git_reference,
                                branch: *const git_reference) -> c_int;
+    pub fn git_branch_upstream_name(out: *mut git_buf,
+                                    repo: *mut git_repository,
+                                    refname: *const c_char) -> c_int;
+    pub fn git_branch_upstream_remote(out: *mut git_buf,
+                                      repo: *mut git_repository,
+                                      refname: *const c_char) -> c_int;
 
     // index
     pub fn git_index_add(index: *mut git_index,
@@ -2021,12 +2196,23 @@ extern {
                                   ancestor_entry: *const git_index_entry,
                                   our_entry: *const git_index_entry,
                                   their_entry: *const git_index_entry) -> c_int;
+    pub fn git_index_conflict_iterator_new(iter: *mut *mut git_index_conflict_iterator,
+                                           index: *mut git_index) -> c_int;
+    pub fn git_index_conflict_next(ancestor_out: *mut *const git_index_entry,
+                                   our_out: *mut *const git_index_entry,
+                                   their_out: *mut *const git_index_entry,
+                                   iter: *mut git_index_conflict_iterator)
+                                   -> c_int;
+    pub fn git_index_conflict_iterator_free(iter: *mut git_index_conflict_iterator);
     pub fn git_index_clear(index: *mut git_index) -> c_int;
     pub fn git_index_entry_stage(entry: *const git_index_entry) -> c_int;
     pub fn git_index_entrycount(entry: *const git_index) -> size_t;
     pub fn git_index_find(at_pos: *mut size_t,
                           index: *mut git_index,
                           path: *const c_char) -> c_int;
+    pub fn git_index_find_prefix(at_pos: *mut size_t,
+                                 index: *mut git_index,
+                                 prefix: *const c_char) -> c_int;
     pub fn git_index_free(index: *mut git_index);
     pub fn git_index_get_byindex(index: *mut git_index,
                                  n: size_t) -> *const git_index_entry;
@@ -2253,6 +2439,23 @@ extern {
     pub fn git_annotated_commit_free(commit: *mut git_annotated_commit);
     pub fn git_merge_init_options(opts: *mut git_merge_options,
                                   version: c_uint) -> c_int;
+    pub fn git_merge_file_init_input(opts: *mut git_merge_file_input,
+                                     version: c_uint) -> c_int;
+    pub fn git_merge_file_init_options(opts: *mut git_merge_file_options,
+                                       version: c_uint) -> c_int;
+    pub fn git_merge_file(out: *mut git_merge_file_result,
+                          ancestor: *const git_merge_file_input,
+                          ours: *const git_merge_file_input,
+                          theirs: *const git_merge_file_input,
+                          opts: *const git_merge_file_options) -> c_int;
+    pub fn git_merge_file_from_index(out: *mut git_merge_file_result,
+                                     repo: *mut git_repository,
+                                     ancestor: *const git_index_entry,
+                                     ours: *const git_index_entry,
+                                     theirs: *const git_index_entry,
+                                     opts: *const git_merge_file_options)
+                                     -> c_int;
+    pub fn git_merge_file_result_free(result: *mut git_merge_file_result);
     pub fn git_merge(repo: *mut git_repository,
                      their_heads: *mut *const git_annotated_commit,
                      len: size_t,
@@ -2270,6 +2473,30 @@ extern {
                            their_tree: *const git_tree,
                            opts: *const git_merge_options) -> c_int;
     pub fn git_repository_state_cleanup(repo: *mut git_repository) -> c_int;
+    pub fn git_repository_message(out: *mut git_buf,
+                                  repo: *mut git_repository) -> c_int;
+    pub fn git_repository_message_remove(repo: *mut git_repository) -> c_int;
+    pub fn git_repository_fetchhead_foreach(repo: *mut git_repository,
+                                            callback: git_repository_fetchhead_foreach_cb,
+                                            payload: *mut c_void) -> c_int;
+    pub fn git_repository_mergehead_foreach(repo: *mut git_repository,
+                                            callback: git_repository_mergehead_foreach_cb,
+                                            payload: *mut c_void) -> c_int;
+
+    // cherrypick / revert
+    pub fn git_cherrypick_commit(out: *mut *mut git_index,
+                                 repo: *mut git_repository,
+                                 cherrypick_commit: *mut git_commit,
+                                 our_commit: *mut git_commit,
+                                 mainline: c_uint,
+                                 merge_options: *const git_merge_options)
+                                 -> c_int;
+    pub fn git_revert_commit(out: *mut *mut git_index,
+                             repo: *mut git_repository,
+                             revert_commit: *mut git_commit,
+                             our_commit: *mut git_commit,
+                             mainline: c_uint,
+                             merge_options: *const git_merge_options) -> c_int;
 
     // merge analysis
 
@@ -2318,6 +2545,10 @@ extern {
                           path: *const c_char,
                           options: *mut git_blame_options) -> c_int;
     pub fn git_blame_free(blame: *mut git_blame);
+    pub fn git_blame_buffer(out: *mut *mut git_blame,
+                            reference: *mut git_blame,
+                            buffer: *const c_char,
+                            buffer_len: size_t) -> c_int;
 
     pub fn git_blame_init_options(opts: *mut git_blame_options,
                                   version: c_uint) -> c_int;
@@ -2356,7 +2587,7 @@ extern {
     pub fn git_revwalk_hide_glob(walk: *mut git_revwalk,
                                  refname: *const c_char) -> c_int;
     pub fn git_revwalk_add_hide_cb(walk: *mut git_revwalk,
-                                   hide_cb: git_revwalk_hide_cb,
+                                   hide_cb: Option<git_revwalk_hide_cb>,
                                    payload: *mut c_void) -> c_int;
 
     pub fn git_revwalk_next(out: *mut git_oid, walk: *mut git_revwalk) -> c_int;
@@ -2372,6 +2603,16 @@ extern {
                            one: *const git_oid,
                            two: *const git_oid) -> c_int;
 
+    pub fn git_merge_base_many(out: *mut git_oid,
+                               repo: *mut git_repository,
+                               length: size_t,
+                               input_array: *const git_oid) -> c_int;
+
+    pub fn git_merge_base_octopus(out: *mut git_oid,
+                                  repo: *mut git_repository,
+                                  length: size_t,
+                                  input_array: *const git_oid) -> c_int;
+
     // pathspec
     pub fn git_pathspec_free(ps: *mut git_pathspec);
     pub fn git_pathspec_match_diff(out: *mut *mut git_pathspec_match_list,
@@ -2424,10 +2665,10 @@ extern {
                           new_blob: *const git_blob,
                           new_as_path: *const c_char,
                           options: *const git_diff_options,
-                          file_cb: git_diff_file_cb,
-                          binary_cb: git_diff_binary_cb,
-                          hunk_cb: git_diff_hunk_cb,
-                          line_cb: git_diff_line_cb,
+                          file_cb: Option<git_diff_file_cb>,
+                          binary_cb: Option<git_diff_binary_cb>,
+                          hunk_cb: Option<git_diff_hunk_cb>,
+                          line_cb: Option<git_diff_line_cb>,
                           payload: *mut c_void) -> c_int;
     pub fn git_diff_buffers(old_buffer: *const c_void,
                             old_len: size_t,
@@ -2662,6 +2903,10 @@ extern {
     // odb
     pub fn git_repository_odb(out: *mut *mut git_odb,
                               repo: *mut git_repository) -> c_int;
+    pub fn git_repository_wrap_odb(out: *mut *mut git_repository,
+                                   odb: *mut git_odb) -> c_int;
+    pub fn git_repository_set_odb(repo: *mut git_repository,
+                                  odb: *mut git_odb);
     pub fn git_odb_free(db: *mut git_odb);
     pub fn git_odb_open_rstream(out: *mut *mut git_odb_stream,
                                 db: *mut git_odb,
@@ -2679,7 +2924,84 @@ extern {
     pub fn git_odb_stream_finalize_write(id: *mut git_oid,
                                          stream: *mut git_odb_stream) -> c_int;
     pub fn git_odb_stream_free(stream: *mut git_odb_stream);
+    pub fn git_odb_write_pack(out: *mut *mut git_odb_writepack,
+                              db: *mut git_odb,
+                              progress_cb: Option<git_transfer_progress_cb>,
+                              progress_payload: *mut c_void) -> c_int;
     pub fn git_odb_foreach(db: *mut git_odb, cb: git_odb_foreach_cb, payload: *mut c_void) -> c_int;
+    pub fn git_odb_exists(db: *mut git_odb, id: *const git_oid) -> c_int;
+    pub fn git_odb_refresh(db: *mut git_odb) -> c_int;
+    pub fn git_odb_hash(out: *mut git_oid,
+                        data: *const c_void,
+                        len: size_t,
+                        otype: git_otype) -> c_int;
+    pub fn git_odb_hashfile(out: *mut git_oid,
+                            path: *const c_char,
+                            otype: git_otype) -> c_int;
+
+    // opts
+    pub fn git_libgit2_opts(option: c_int, ...) -> c_int;
+
+    // worktree
+    pub fn git_worktree_list(out: *mut git_strarray,
+                             repo: *mut git_repository) -> c_int;
+    pub fn git_worktree_lookup(out: *mut *mut git_worktree,
+                               repo: *mut git_repository,
+                               name: *const c_char) -> c_int;
+    pub fn git_worktree_free(wt: *mut git_worktree);
+    pub fn git_worktree_validate(wt: *const git_worktree) -> c_int;
+    pub fn git_worktree_add_init_options(opts: *mut git_worktree_add_options,
+                                         version: c_uint) -> c_int;
+    pub fn git_worktree_add(out: *mut *mut git_worktree,
+                            repo: *mut git_repository,
+                            name: *const c_char,
+                            path: *const c_char,
+                            opts: *const git_worktree_add_options) -> c_int;
+    pub fn git_worktree_lock(wt: *mut git_worktree,
+                             reason: *const c_char) -> c_int;
+    pub fn git_worktree_unlock(wt: *mut git_worktree) -> c_int;
+    pub fn git_worktree_is_locked(reason: *mut git_buf,
+                                  wt: *const git_worktree) -> c_int;
+
+    // transaction
+    pub fn git_transaction_new(out: *mut *mut git_transaction,
+                               repo: *mut git_repository) -> c_int;
+    pub fn git_transaction_lock_ref(tx: *mut git_transaction,
+                                    refname: *const c_char) -> c_int;
+    pub fn git_transaction_set_target(tx: *mut git_transaction,
+                                      refname: *const c_char,
+                                      target: *const git_oid,
+                                      sig: *const git_signature,
+                                      msg: *const c_char) -> c_int;
+    pub fn git_transaction_set_symbolic_target(tx: *mut git_transaction,
+                                               refname: *const c_char,
+                                               target: *const c_char,
+                                               sig: *const git_signature,
+                                               msg: *const c_char) -> c_int;
+    pub fn git_transaction_remove(tx: *mut git_transaction,
+                                  refname: *const c_char) -> c_int;
+    pub fn git_transaction_commit(tx: *mut git_transaction) -> c_int;
+    pub fn git_transaction_free(tx: *mut git_transaction);
+
+    // attr
+    pub fn git_attr_value(attr: *const c_char) -> git_attr_t;
+    pub fn git_attr_get(value_out: *mut *const c_char,
+                        repo: *mut git_repository,
+                        flags: u32,
+                        path: *const c_char,
+                        name: *const c_char) -> c_int;
+    pub fn git_attr_get_many(values_out: *mut *const c_char,
+                             repo: *mut git_repository,
+                             flags: u32,
+                             path: *const c_char,
+                             num_attr: size_t,
+                             names: *mut *const c_char) -> c_int;
+    pub fn git_attr_foreach(repo: *mut git_repository,
+                            flags: u32,
+                            path: *const c_char,
+                            callback: git_attr_foreach_cb,
+                            payload: *mut c_void) -> c_int;
+    pub fn git_attr_cache_flush(repo: *mut git_repository);
 }
 
 pub fn init() {
@@ -0,0 +1,78 @@
+use std::ffi::CStr;
+use std::str;
+use libc::{c_char, c_int, c_void};
+
+use {raw, panic};
+
+/// The value of a gitattribute as returned by an attribute lookup.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AttrValue<'string> {
+    /// The attribute is set (e.g. `text`).
+    True,
+    /// The attribute is unset (e.g. `-text`).
+    False,
+    /// The attribute is set to a UTF-8 string value (e.g. `eol=crlf`).
+    String(&'string str),
+    /// The attribute is set to a value which is not valid UTF-8.
+    Bytes(&'string [u8]),
+    /// The attribute is not specified for the path.
+    Unspecified,
+}
+
+impl<'string> AttrValue<'string> {
+    /// Returns the string value of the attribute, if it has one which is
+    /// valid UTF-8.
+    pub fn as_str(&self) -> Option<&'string str> {
+        match *self {
+            AttrValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Interpret a raw attribute value as returned by libgit2.
+///
+/// The pointer must be null or valid for the lifetime `'a`.
+pub unsafe fn value_from_raw<'a>(value: *const c_char) -> AttrValue<'a> {
+    match raw::git_attr_value(value) {
+        raw::GIT_ATTR_TRUE_T => AttrValue::True,
+        raw::GIT_ATTR_FALSE_T => AttrValue::False,
+        raw::GIT_ATTR_VALUE_T => {
+            let bytes = CStr::from_ptr(value).to_bytes();
+            match str::from_utf8(bytes) {
+                Ok(s) => AttrValue::String(s),
+                Err(..) => AttrValue::Bytes(bytes),
+            }
+        }
+        _ => AttrValue::Unspecified,
+    }
+}
+
+/// Callback used by `Repository::attr_foreach`.
+///
+/// It receives the name, as bytes, and value of each attribute set on the
+/// path and returns `true` to continue iterating or `false` to stop.
+pub type AttrForeachCb<'a> = FnMut(&[u8], AttrValue) -> bool + 'a;
+
+#[allow(missing_docs)]
+pub struct AttrForeachCbData<'a> {
+    pub callback: &'a mut AttrForeachCb<'a>
+}
+
+#[allow(unused)]
+pub extern fn attr_foreach_cb(name: *const c_char,
+                              value: *const c_char,
+                              payload: *mut c_void)
+                              -> c_int
+{
+    panic::wrap(|| unsafe {
+        let data = &mut *(payload as *mut AttrForeachCbData);
+        let name = CStr::from_ptr(name).to_bytes();
+        let res = {
+            let callback = &mut data.callback;
+            callback(name, value_from_raw(value))
+        };
+
+        if res { 0 } else { 1 }
+    }).unwrap_or(1)
+}
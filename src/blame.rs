@@ -1,9 +1,11 @@
 use std::marker;
-use {raw, Repository, Oid, signature, Signature};
+use {raw, Error, Repository, Oid, signature, Signature};
 use util::{self, Binding};
 use std::path::Path;
 use std::ops::Range;
 use std::mem;
+use std::ptr;
+use libc::{c_char, size_t};
 
 /// Opaque structure to hold blame results.
 pub struct Blame<'repo> {
@@ -70,6 +72,21 @@ impl<'repo> Blame<'repo> {
         BlameIter { range: 0..self.len(), blame: self }
     }
 
+    /// Get blame data for a file that has been modified in memory.
+    ///
+    /// This blame is used as the reference: `buffer` is diffed against the
+    /// content it was computed for, and lines which were added are attributed
+    /// to a hunk with a zero commit id as they're not yet committed.
+    pub fn buffer(&self, buffer: &[u8]) -> Result<Blame<'repo>, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_blame_buffer(&mut raw, self.raw,
+                                            buffer.as_ptr() as *const c_char,
+                                            buffer.len() as size_t));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
 }
 
 impl<'blame> BlameHunk<'blame> {
@@ -275,7 +292,9 @@ impl<'blame> ExactSizeIterator for BlameIter<'blame> {}
 
 #[cfg(test)]
 mod tests {
+    use BlameOptions;
     use std::fs::{self, File};
+    use std::io::prelude::*;
     use std::path::Path;
 
     #[test]
@@ -311,5 +330,68 @@ mod tests {
         assert!(!hunk.is_boundary())
     }
 
-}
+    #[test]
+    fn buffer() {
+        let (td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("foo")).unwrap()
+            .write_all(b"a\nb\n").unwrap();
+        index.add_path(Path::new("foo")).unwrap();
 
+        let id = index.write_tree().unwrap();
+        let tree = repo.find_tree(id).unwrap();
+        let sig = repo.signature().unwrap();
+        let id = repo.refname_to_id("HEAD").unwrap();
+        let parent = repo.find_commit(id).unwrap();
+        let commit = repo.commit(Some("HEAD"), &sig, &sig, "commit",
+                                 &tree, &[&parent]).unwrap();
+
+        let blame = repo.blame_file(Path::new("foo"), None).unwrap();
+        assert_eq!(blame.len(), 1);
+
+        let blame = blame.buffer(b"a\nb\nc\n").unwrap();
+        assert_eq!(blame.len(), 2);
+        let first = blame.get_index(0).unwrap();
+        assert_eq!(first.final_commit_id(), commit);
+        assert_eq!(first.lines_in_hunk(), 2);
+        let last = blame.get_index(1).unwrap();
+        assert!(last.final_commit_id().is_zero());
+        assert_eq!(last.final_start_line(), 3);
+        assert_eq!(last.lines_in_hunk(), 1);
+        assert_eq!(blame.get_line(3).unwrap().final_commit_id(),
+                   last.final_commit_id());
+    }
+
+    #[test]
+    fn newest_commit() {
+        let (td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let commit = |content: &[u8]| {
+            File::create(&td.path().join("foo")).unwrap()
+                .write_all(content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("foo")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.refname_to_id("HEAD").unwrap();
+            let parent = repo.find_commit(head).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "commit",
+                        &tree, &[&parent]).unwrap()
+        };
+        let first = commit(b"a\n");
+        let last = commit(b"a\nb\n");
+
+        let blame = repo.blame_file(Path::new("foo"), None).unwrap();
+        assert_eq!(blame.len(), 2);
+        assert_eq!(blame.get_line(2).unwrap().final_commit_id(), last);
+
+        let mut opts = BlameOptions::new();
+        opts.newest_commit(first)
+            .first_parent(true)
+            .track_copies_same_file(true)
+            .track_copies_same_commit_moves(true);
+        let blame = repo.blame_file(Path::new("foo"), Some(&mut opts)).unwrap();
+        assert_eq!(blame.len(), 1);
+        assert!(blame.iter().all(|hunk| hunk.final_commit_id() != last));
+        assert_eq!(blame.get_index(0).unwrap().final_commit_id(), first);
+    }
+}
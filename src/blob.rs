@@ -127,7 +127,10 @@ impl<'repo> io::Write for BlobWriter<'repo> {
         unsafe {
             let res = ((*self.raw).write)(self.raw, buf.as_ptr() as *const _, buf.len());
             if res < 0 {
-                Err(io::Error::new(io::ErrorKind::Other, "Write error"))
+                let err = Error::last_error(res).unwrap_or_else(|| {
+                    Error::from_str("failed to write to blob stream")
+                });
+                Err(io::Error::new(io::ErrorKind::Other, err))
             } else {
                 Ok(buf.len())
             }
@@ -142,7 +145,7 @@ mod tests {
     use std::fs::File;
     use std::path::Path;
     use tempdir::TempDir;
-    use Repository;
+    use {Repository, Oid, ObjectType};
 
     #[test]
     fn buffer() {
@@ -183,4 +186,32 @@ mod tests {
         assert_eq!(blob.content(), [10, 11, 12]);
         blob.into_object();
     }
+
+    #[test]
+    fn stream_chunks() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let data: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+
+        let mut ws = repo.blob_writer(None).unwrap();
+        for chunk in data.chunks(64 * 1024) {
+            ws.write_all(chunk).unwrap();
+        }
+        let id = ws.commit().unwrap();
+        assert_eq!(id, Oid::hash_object(ObjectType::Blob, &data).unwrap());
+        let blob = repo.find_blob(id).unwrap();
+        assert_eq!(blob.content().len(), data.len());
+    }
+
+    #[test]
+    fn stream_drop_without_commit() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        {
+            let mut ws = repo.blob_writer(None).unwrap();
+            ws.write_all(b"never committed").unwrap();
+        }
+        let id = Oid::hash_object(ObjectType::Blob, b"never committed").unwrap();
+        assert!(repo.find_blob(id).is_err());
+    }
 }
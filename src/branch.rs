@@ -2,8 +2,9 @@ use std::ffi::CString;
 use std::marker;
 use std::ptr;
 use std::str;
+use libc::c_int;
 
-use {raw, Error, Reference, BranchType, References};
+use {raw, Buf, Error, ErrorCode, Reference, BranchType, References};
 use util::Binding;
 
 /// A structure to represent a git [branch][1]
@@ -81,6 +82,21 @@ impl<'repo> Branch<'repo> {
         }
     }
 
+    /// Return the full name of the upstream branch of a local branch, such as
+    /// `refs/remotes/origin/master`, without looking the upstream up.
+    ///
+    /// Returns `Ok(None)` if the branch has no upstream configured.
+    pub fn upstream_name(&self) -> Result<Option<String>, Error> {
+        let buf = Buf::new();
+        let rc = unsafe {
+            let reference = &*self.get().raw();
+            raw::git_branch_upstream_name(buf.raw(),
+                                          raw::git_reference_owner(reference),
+                                          raw::git_reference_name(reference))
+        };
+        upstream_string(rc, &buf)
+    }
+
     /// Set the upstream configuration for a given local branch.
     ///
     /// If `None` is specified, then the upstream branch is unset. The name
@@ -96,6 +112,17 @@ impl<'repo> Branch<'repo> {
     }
 }
 
+/// Interpret the result of a libgit2 call which wrote an upstream's name
+/// into `buf`, mapping a missing upstream to `None`.
+pub fn upstream_string(rc: c_int, buf: &Buf) -> Result<Option<String>, Error> {
+    match ::call::try(rc) {
+        Ok(..) => {}
+        Err(ref e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    Ok(Some(try!(str::from_utf8(buf)).to_string()))
+}
+
 impl<'repo> Branches<'repo> {
     /// Creates a new iterator from the raw pointer given.
     ///
@@ -159,4 +186,26 @@ mod tests {
 
         b1.delete().unwrap();
     }
+
+    #[test]
+    fn upstream_name() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap();
+        let target = head.target().unwrap();
+        let commit = repo.find_commit(target).unwrap();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.reference("refs/remotes/origin/master", target, false,
+                       "fetch").unwrap();
+
+        let mut branch = repo.branch("topic", &commit, false).unwrap();
+        assert_eq!(branch.upstream_name().unwrap(), None);
+        assert_eq!(repo.branch_upstream_remote("refs/heads/topic").unwrap(),
+                   None);
+
+        branch.set_upstream(Some("origin/master")).unwrap();
+        assert_eq!(branch.upstream_name().unwrap(),
+                   Some("refs/remotes/origin/master".to_string()));
+        assert_eq!(repo.branch_upstream_remote("refs/heads/topic").unwrap(),
+                   Some("origin".to_string()));
+    }
 }
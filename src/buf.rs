@@ -33,6 +33,9 @@ impl Buf {
         }
     }
 
+    /// View this buffer as a byte slice.
+    pub fn as_slice(&self) -> &[u8] { &**self }
+
     /// Attempt to view this buffer as a string slice.
     ///
     /// Returns `None` if the buffer is not valid utf-8.
@@ -42,6 +45,9 @@ impl Buf {
 impl Deref for Buf {
     type Target = [u8];
     fn deref(&self) -> &[u8] {
+        if self.raw.ptr.is_null() {
+            return &[]
+        }
         unsafe {
             slice::from_raw_parts(self.raw.ptr as *const u8,
                                   self.raw.size as usize)
@@ -51,6 +57,9 @@ impl Deref for Buf {
 
 impl DerefMut for Buf {
     fn deref_mut(&mut self) -> &mut [u8] {
+        if self.raw.ptr.is_null() {
+            return &mut []
+        }
         unsafe {
             slice::from_raw_parts_mut(self.raw.ptr as *mut u8,
                                       self.raw.size as usize)
@@ -71,3 +80,28 @@ impl Drop for Buf {
         unsafe { raw::git_buf_free(&mut self.raw) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use Buf;
+
+    #[test]
+    fn smoke() {
+        let buf = Buf::new();
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_slice(), b"");
+        assert_eq!(buf.as_str(), Some(""));
+    }
+
+    #[test]
+    fn short_id() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head()).target().unwrap();
+        let obj = t!(repo.find_object(head, None));
+        let buf = t!(obj.short_id());
+        let hex = head.to_string();
+        assert!(buf.len() >= 7);
+        assert_eq!(&buf[..], hex[..buf.len()].as_bytes());
+        assert_eq!(buf.as_str(), Some(&hex[..buf.len()]));
+    }
+}
@@ -48,11 +48,14 @@ pub type Progress<'a> = FnMut(Option<&Path>, usize, usize) + 'a;
 ///
 /// The first argument is the notification type, the next is the path for the
 /// the notification, followed by the baseline diff, target diff, and workdir diff.
+/// Each diff file is `None` if the file doesn't exist on that side, for
+/// example the workdir file of a path being newly checked out.
 ///
 /// The callback must return a bool specifying whether the checkout should
 /// continue.
-pub type Notify<'a> = FnMut(CheckoutNotificationType, Option<&Path>, DiffFile,
-                            DiffFile, DiffFile) -> bool + 'a;
+pub type Notify<'a> = FnMut(CheckoutNotificationType, Option<&Path>,
+                            Option<DiffFile>, Option<DiffFile>,
+                            Option<DiffFile>) -> bool + 'a;
 
 
 impl<'cb> Default for RepoBuilder<'cb> {
@@ -416,11 +419,14 @@ impl<'cb> CheckoutBuilder<'cb> {
 
     /// Set a callback to receive checkout notifications.
     ///
-    /// Callbacks are invoked prior to modifying any files on disk.
-    /// Returning `false` from the callback will cancel the checkout.
+    /// Callbacks are invoked prior to modifying any files on disk, for the
+    /// notification types selected with `notify_on`. Returning `false` from
+    /// the callback will cancel the checkout, which then fails with
+    /// `ErrorCode::User`.
     pub fn notify<F>(&mut self, cb: F) -> &mut CheckoutBuilder<'cb>
-        where F: FnMut(CheckoutNotificationType, Option<&Path>, DiffFile,
-                       DiffFile, DiffFile) -> bool + 'cb
+        where F: FnMut(CheckoutNotificationType, Option<&Path>,
+                       Option<DiffFile>, Option<DiffFile>,
+                       Option<DiffFile>) -> bool + 'cb
     {
         self.notify = Some(Box::new(cb) as Box<Notify<'cb>>);
         self
@@ -509,11 +515,11 @@ extern fn notify_cb(why: raw::git_checkout_notify_t,
         let why = CheckoutNotificationType::from_bits_truncate(why as u32);
         let keep_going = callback(why,
                                   path,
-                                  DiffFile::from_raw(baseline),
-                                  DiffFile::from_raw(target),
-                                  DiffFile::from_raw(workdir));
-        if keep_going {0} else {1}
-    }).unwrap_or(2)
+                                  Binding::from_raw_opt(baseline),
+                                  Binding::from_raw_opt(target),
+                                  Binding::from_raw_opt(workdir));
+        if keep_going {0} else {raw::GIT_EUSER}
+    }).unwrap_or(-1)
 }
 
 #[cfg(test)]
@@ -521,8 +527,10 @@ mod tests {
     use std::fs;
     use std::path::Path;
     use tempdir::TempDir;
-    use super::RepoBuilder;
-    use Repository;
+    use std::io::prelude::*;
+    use super::{RepoBuilder, CheckoutBuilder};
+    use {Repository, FetchOptions, RemoteCallbacks, Progress, ErrorCode};
+    use {CHECKOUT_NOTIFICATION_CONFLICT, CHECKOUT_NOTIFICATION_UPDATED};
 
     #[test]
     fn smoke() {
@@ -553,4 +561,78 @@ mod tests {
                                   .clone(&url, &dst).is_err());
     }
 
+    #[test]
+    fn clone_progress() {
+        let (td, _repo) = ::test::repo_init();
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let mut last: Option<Progress<'static>> = None;
+        {
+            let mut callbacks = RemoteCallbacks::new();
+            callbacks.transfer_progress(|progress| {
+                last = Some(progress.to_owned());
+                true
+            });
+            let mut fetch_opts = FetchOptions::new();
+            fetch_opts.remote_callbacks(callbacks);
+            RepoBuilder::new().fetch_options(fetch_opts)
+                              .clone(&url, td2.path()).unwrap();
+        }
+        let last = last.unwrap();
+        assert!(last.total_objects() > 0);
+        assert_eq!(last.received_objects(), last.total_objects());
+        assert_eq!(last.indexed_objects(), last.total_objects());
+    }
+
+    #[test]
+    fn checkout_notify_conflict() {
+        let (td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let blob = t!(repo.blob(b"theirs\n"));
+        let mut builder = t!(repo.treebuilder(None));
+        t!(builder.insert("foo", blob, 0o100644));
+        let tree = t!(repo.find_tree(t!(builder.write())));
+        let id = t!(repo.commit(None, &sig, &sig, "foo", &tree, &[&head]));
+        let commit = t!(repo.find_object(id, None));
+
+        t!(t!(fs::File::create(td.path().join("foo"))).write_all(b"dirty\n"));
+
+        let mut conflicts = Vec::new();
+        let res = {
+            let mut opts = CheckoutBuilder::new();
+            opts.notify_on(CHECKOUT_NOTIFICATION_CONFLICT)
+                .notify(|kind, path, baseline, target, workdir| {
+                    assert!(baseline.is_none());
+                    assert!(target.is_some());
+                    assert!(workdir.is_some());
+                    conflicts.push((kind, path.unwrap().to_path_buf()));
+                    true
+                });
+            repo.checkout_tree(&commit, Some(&mut opts))
+        };
+        assert!(res.is_err());
+        assert_eq!(conflicts, vec![(CHECKOUT_NOTIFICATION_CONFLICT,
+                                    Path::new("foo").to_path_buf())]);
+
+        t!(fs::remove_file(td.path().join("foo")));
+        let err = {
+            let mut opts = CheckoutBuilder::new();
+            opts.notify_on(CHECKOUT_NOTIFICATION_UPDATED)
+                .notify(|_, _, _, _, _| false);
+            repo.checkout_tree(&commit, Some(&mut opts)).err().unwrap()
+        };
+        assert_eq!(err.code(), ErrorCode::User);
+        assert!(!td.path().join("foo").exists());
+
+        let mut steps = Vec::new();
+        {
+            let mut opts = CheckoutBuilder::new();
+            opts.progress(|_, completed, total| steps.push((completed, total)));
+            t!(repo.checkout_tree(&commit, Some(&mut opts)));
+        }
+        assert!(td.path().join("foo").exists());
+        assert_eq!(steps.last(), Some(&(1, 1)));
+    }
 }
@@ -1,3 +1,4 @@
+use std::cmp;
 use std::marker;
 use std::mem;
 use std::ops::Range;
@@ -5,7 +6,8 @@ use std::ptr;
 use std::str;
 use libc;
 
-use {raw, signature, Oid, Error, Signature, Tree, Time, Object};
+use {raw, signature, Oid, Error, Signature, Tree, Time, Object, Revwalk};
+use SORT_TOPOLOGICAL;
 use util::Binding;
 
 /// A structure to represent a git [commit][1]
@@ -28,6 +30,13 @@ pub struct ParentIds<'commit> {
     commit: &'commit Commit<'commit>,
 }
 
+/// An iterator over a commit and all of its ancestors, created by
+/// `Commit::ancestors`.
+pub struct Ancestors<'repo> {
+    walk: Revwalk<'repo>,
+    repo: *mut raw::git_repository,
+}
+
 impl<'repo> Commit<'repo> {
     /// Get the id (SHA1) of a repository commit
     pub fn id(&self) -> Oid {
@@ -42,6 +51,9 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Get the tree pointed to by a commit.
+    ///
+    /// This looks the tree up in the object database; use `tree_id` if only
+    /// its id is needed.
     pub fn tree(&self) -> Result<Tree<'repo>, Error> {
         let mut ret = ptr::null_mut();
         unsafe {
@@ -78,10 +90,16 @@ impl<'repo> Commit<'repo> {
     ///
     /// `None` will be returned if the encoding is not known
     pub fn message_encoding(&self) -> Option<&str> {
-        let bytes = unsafe {
-            ::opt_bytes(self, raw::git_commit_message(&*self.raw))
-        };
-        bytes.map(|b| str::from_utf8(b).unwrap())
+        self.message_encoding_bytes().and_then(|b| str::from_utf8(b).ok())
+    }
+
+    /// Get the encoding for the message of a commit as a byte slice.
+    ///
+    /// `None` will be returned if the encoding is not known
+    pub fn message_encoding_bytes(&self) -> Option<&[u8]> {
+        unsafe {
+            ::opt_bytes(self, raw::git_commit_message_encoding(&*self.raw))
+        }
     }
 
     /// Get the full raw message of a commit.
@@ -145,19 +163,25 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Get the number of parents of this commit.
+    pub fn parent_count(&self) -> usize {
+        unsafe { raw::git_commit_parentcount(&*self.raw) as usize }
+    }
+
     /// Creates a new iterator over the parents of this commit.
     pub fn parents<'a>(&'a self) -> Parents<'a, 'repo> {
-        let max = unsafe { raw::git_commit_parentcount(&*self.raw) as usize };
-        Parents { range: 0..max, commit: self }
+        Parents { range: 0..self.parent_count(), commit: self }
     }
 
     /// Creates a new iterator over the parents of this commit.
     pub fn parent_ids(&self) -> ParentIds {
-        let max = unsafe { raw::git_commit_parentcount(&*self.raw) as usize };
-        ParentIds { range: 0..max, commit: self }
+        ParentIds { range: 0..self.parent_count(), commit: self }
     }
 
     /// Get the author of this commit.
+    ///
+    /// The returned signature borrows from this commit, so no allocation is
+    /// needed to inspect it.
     pub fn author(&self) -> Signature {
         unsafe {
             let ptr = raw::git_commit_author(&*self.raw);
@@ -166,6 +190,9 @@ impl<'repo> Commit<'repo> {
     }
 
     /// Get the committer of this commit.
+    ///
+    /// The returned signature borrows from this commit, so no allocation is
+    /// needed to inspect it.
     pub fn committer(&self) -> Signature {
         unsafe {
             let ptr = raw::git_commit_committer(&*self.raw);
@@ -208,12 +235,14 @@ impl<'repo> Commit<'repo> {
 
     /// Get the specified parent of the commit.
     ///
-    /// Use the `parents` iterator to return an iterator over all parents.
+    /// Use the `parents` iterator to return an iterator over all parents. An
+    /// error with code `ErrorCode::NotFound` is returned if `i` is not less
+    /// than `parent_count`.
     pub fn parent(&self, i: usize) -> Result<Commit<'repo>, Error> {
         unsafe {
             let mut raw = ptr::null_mut();
             try_call!(raw::git_commit_parent(&mut raw, &*self.raw,
-                                             i as libc::c_uint));
+                                             parent_index(i)));
             Ok(Binding::from_raw(raw))
         }
     }
@@ -224,9 +253,10 @@ impl<'repo> Commit<'repo> {
     /// parent commit from the ODB.
     ///
     /// Use the `parent_ids` iterator to return an iterator over all parents.
+    /// An error is returned if `i` is not less than `parent_count`.
     pub fn parent_id(&self, i: usize) -> Result<Oid, Error> {
         unsafe {
-            let id = raw::git_commit_parent_id(self.raw, i as libc::c_uint);
+            let id = raw::git_commit_parent_id(self.raw, parent_index(i));
             if id.is_null() {
                 Err(Error::from_str("parent index out of bounds"))
             } else {
@@ -235,6 +265,24 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Walk this commit and all of its ancestors.
+    ///
+    /// Commits are yielded in topological order, starting with this commit
+    /// and never visiting a parent before all of its children. This is a
+    /// shorthand for pushing the commit onto a fresh `Revwalk` and looking up
+    /// each id it returns.
+    pub fn ancestors(self) -> Result<Ancestors<'repo>, Error> {
+        unsafe {
+            let repo = raw::git_commit_owner(&*self.raw);
+            let mut raw = ptr::null_mut();
+            try_call!(raw::git_revwalk_new(&mut raw, repo));
+            let mut walk: Revwalk<'repo> = Binding::from_raw(raw);
+            walk.set_sorting(SORT_TOPOLOGICAL);
+            try!(walk.push(self.id()));
+            Ok(Ancestors { walk: walk, repo: repo })
+        }
+    }
+
     /// Casts this Commit to be usable as an `Object`
     pub fn as_object(&self) -> &Object<'repo> {
         unsafe {
@@ -251,6 +299,12 @@ impl<'repo> Commit<'repo> {
     }
 }
 
+/// Convert a parent index for libgit2, saturating rather than truncating so
+/// that indices which don't fit in a `c_uint` stay out of bounds.
+fn parent_index(i: usize) -> libc::c_uint {
+    cmp::min(i, libc::c_uint::max_value() as usize) as libc::c_uint
+}
+
 impl<'repo> Binding for Commit<'repo> {
     type Raw = *mut raw::git_commit;
     unsafe fn from_raw(raw: *mut raw::git_commit) -> Commit<'repo> {
@@ -305,6 +359,23 @@ impl<'commit> DoubleEndedIterator for ParentIds<'commit> {
 
 impl<'commit> ExactSizeIterator for ParentIds<'commit> {}
 
+impl<'repo> Iterator for Ancestors<'repo> {
+    type Item = Result<Commit<'repo>, Error>;
+    fn next(&mut self) -> Option<Result<Commit<'repo>, Error>> {
+        let id = match self.walk.next() {
+            Some(Ok(id)) => id,
+            Some(Err(e)) => return Some(Err(e)),
+            None => return None,
+        };
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call_iter!(raw::git_commit_lookup(&mut raw, self.repo,
+                                                  id.raw()));
+            Some(Ok(Binding::from_raw(raw)))
+        }
+    }
+}
+
 impl<'repo> Clone for Commit<'repo> {
     fn clone(&self) -> Self {
         self.as_object().clone().into_commit().ok().unwrap()
@@ -319,6 +390,10 @@ impl<'repo> Drop for Commit<'repo> {
 
 #[cfg(test)]
 mod tests {
+    use std::io::prelude::*;
+    use tempdir::TempDir;
+    use {Repository, Signature, ObjectType, Time, ErrorCode};
+
     #[test]
     fn smoke() {
         let (_td, repo) = ::test::repo_init();
@@ -329,7 +404,7 @@ mod tests {
         assert_eq!(commit.id(), target);
         commit.message_raw().unwrap();
         commit.raw_header().unwrap();
-        commit.message_encoding();
+        assert_eq!(commit.message_encoding(), None);
         commit.summary().unwrap();
         commit.tree_id();
         commit.tree().unwrap();
@@ -355,5 +430,176 @@ mod tests {
         repo.find_object(target, None).unwrap().as_commit().unwrap();
         repo.find_object(target, None).unwrap().into_commit().ok().unwrap();
     }
-}
 
+    #[test]
+    fn root_and_child() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        let sig = Signature::now("name", "email").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        assert!(repo.head().is_err());
+
+        let root = repo.commit(Some("HEAD"), &sig, &sig, "root", &tree, &[])
+                       .unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(root));
+        assert_eq!(repo.head().unwrap().name(), Some("refs/heads/master"));
+        let root = repo.find_commit(root).unwrap();
+        assert_eq!(root.parents().count(), 0);
+
+        let dangling = repo.commit(None, &sig, &sig, "dangling", &tree,
+                                   &[&root]).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(root.id()));
+        assert!(repo.find_commit(dangling).is_ok());
+
+        let child = repo.commit(Some("HEAD"), &sig, &sig, "child", &tree,
+                                &[&root]).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(child));
+        let child = repo.find_commit(child).unwrap();
+        assert_eq!(child.parent_ids().collect::<Vec<_>>(), [root.id()]);
+    }
+
+    #[test]
+    fn non_utf8() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+        let tree = repo.find_commit(head).unwrap().tree_id();
+        let mut buf = Vec::new();
+        write!(buf, "tree {}\n", tree).unwrap();
+        buf.extend_from_slice(b"author J\xf6rg <jorg@example.com> 0 +0000\n");
+        buf.extend_from_slice(b"committer J\xf6rg <jorg@example.com> 0 +0000\n");
+        buf.extend_from_slice(b"encoding ISO-8859-1\n\n");
+        buf.extend_from_slice(b"caf\xe9\n\nbody\n");
+
+        let odb = repo.odb().unwrap();
+        let mut writer = odb.writer(buf.len(), ObjectType::Commit).unwrap();
+        writer.write_all(&buf).unwrap();
+        let id = writer.finalize().unwrap();
+
+        let commit = repo.find_commit(id).unwrap();
+        assert_eq!(commit.author().name(), None);
+        assert_eq!(commit.author().name_bytes(), b"J\xf6rg");
+        assert_eq!(commit.committer().name_bytes(), b"J\xf6rg");
+        assert_eq!(commit.author().email(), Some("jorg@example.com"));
+        assert_eq!(commit.message(), None);
+        assert_eq!(commit.message_bytes(), b"caf\xe9\n\nbody\n");
+        assert_eq!(commit.summary(), None);
+        assert_eq!(commit.summary_bytes(), Some(&b"caf\xe9"[..]));
+        assert_eq!(commit.message_encoding(), Some("ISO-8859-1"));
+        assert_eq!(commit.message_encoding_bytes(), Some(&b"ISO-8859-1"[..]));
+    }
+
+    #[test]
+    fn tree_and_tree_id() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let blob = repo.blob(b"contents").unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("file", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let id = repo.commit(None, &sig, &sig, "with file", &tree, &[&head])
+                     .unwrap();
+
+        let commit = repo.find_commit(id).unwrap();
+        assert_eq!(commit.tree_id(), tree.id());
+        let found = commit.tree().unwrap();
+        assert_eq!(found.id(), commit.tree_id());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found.get(0).unwrap().id(), blob);
+        assert!(head.tree_id() != commit.tree_id());
+    }
+
+    #[test]
+    fn author_and_committer() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = head.tree().unwrap();
+        let author = Signature::new("Author", "author@example.com",
+                                    &Time::new(1234567890, 60)).unwrap();
+        let committer = Signature::new("Committer", "committer@example.com",
+                                       &Time::new(1234567990, -120)).unwrap();
+        let id = repo.commit(None, &author, &committer, "authored", &tree,
+                             &[&head]).unwrap();
+
+        let commit = repo.find_commit(id).unwrap();
+        let found = commit.author();
+        assert_eq!(found.name(), Some("Author"));
+        assert_eq!(found.email(), Some("author@example.com"));
+        assert!(found.when() == author.when());
+        let found = commit.committer();
+        assert_eq!(found.name(), Some("Committer"));
+        assert_eq!(found.email(), Some("committer@example.com"));
+        assert!(found.when() == committer.when());
+    }
+
+    #[test]
+    fn parent_out_of_bounds() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let root = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = root.tree().unwrap();
+        let id = repo.commit(None, &sig, &sig, "child", &tree, &[&root])
+                     .unwrap();
+        let child = repo.find_commit(id).unwrap();
+        assert_eq!(root.parent_count(), 0);
+        assert_eq!(child.parent_count(), 1);
+        assert_eq!(child.parent(0).unwrap().id(), root.id());
+        assert_eq!(child.parent_id(0).unwrap(), root.id());
+
+        assert_eq!(child.parent(5).err().unwrap().code(), ErrorCode::NotFound);
+        assert!(child.parent_id(5).is_err());
+        assert!(child.parent(usize::max_value()).is_err());
+        assert!(child.parent_id(usize::max_value()).is_err());
+        assert!(root.parent(0).is_err());
+    }
+
+    #[test]
+    fn clone_outlives_original() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        let commit = repo.find_commit(id).unwrap();
+        let copy = commit.clone();
+        drop(commit);
+        assert_eq!(copy.id(), id);
+        assert_eq!(copy.message(), Some("initial"));
+
+        let tree = copy.tree().unwrap();
+        let tree_copy = tree.clone();
+        drop(tree);
+        assert_eq!(tree_copy.id(), copy.tree_id());
+        drop(copy);
+        assert_eq!(tree_copy.len(), 0);
+
+        let blob = repo.find_blob(repo.blob(b"data").unwrap()).unwrap();
+        let object = blob.as_object().clone();
+        let blob_copy = blob.clone();
+        drop(blob);
+        assert_eq!(blob_copy.content(), b"data");
+        assert_eq!(object.id(), blob_copy.id());
+    }
+
+    #[test]
+    fn ancestors() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let mut ids = vec![t!(repo.head()).target().unwrap()];
+        for message in ["second", "third"].iter() {
+            let parent = t!(repo.find_commit(*ids.last().unwrap()));
+            let tree = t!(parent.tree());
+            ids.push(t!(repo.commit(Some("HEAD"), &sig, &sig, message, &tree,
+                                    &[&parent])));
+        }
+
+        let tip = t!(repo.find_commit(ids[2]));
+        let found = t!(tip.ancestors()).map(|c| t!(c).id())
+            .collect::<Vec<_>>();
+        ids.reverse();
+        assert_eq!(found, ids);
+
+        let root = t!(repo.find_commit(ids[2]));
+        let found = t!(root.ancestors()).map(|c| t!(c).id())
+            .collect::<Vec<_>>();
+        assert_eq!(found, [ids[2]]);
+    }
+}
@@ -135,6 +135,20 @@ impl Config {
         }
     }
 
+    /// Remove multivar config variables in the config file with the highest
+    /// level (usually the local one).
+    ///
+    /// Only the values matching the regular expression `regexp` are removed.
+    pub fn remove_multivar(&mut self, name: &str, regexp: &str)
+                           -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        let regexp = try!(CString::new(regexp));
+        unsafe {
+            try_call!(raw::git_config_delete_multivar(self.raw, name, regexp));
+        }
+        Ok(())
+    }
+
     /// Get the value of a boolean config variable.
     ///
     /// All config files will be looked into, in the order of their defined
@@ -238,7 +252,9 @@ impl Config {
     /// Iterate over all the config variables
     ///
     /// If `glob` is `Some`, then the iterator will only iterate over all
-    /// variables whose name matches the pattern.
+    /// variables whose name matches the pattern. Despite its name, the
+    /// pattern is a regular expression, e.g. `remote\..*\.url` matches the
+    /// url of every configured remote.
     ///
     /// # Example
     ///
@@ -432,10 +448,19 @@ impl<'cfg> ConfigEntry<'cfg> {
     pub fn value(&self) -> Option<&str> { str::from_utf8(self.value_bytes()).ok() }
 
     /// Gets the value of this entry as a byte slice.
+    ///
+    /// Panics if this entry has no value, see `has_value`.
     pub fn value_bytes(&self) -> &[u8] {
         unsafe { ::opt_bytes(self, (*self.raw).value).unwrap() }
     }
 
+    /// Returns `true` if a value exists for this entry.
+    ///
+    /// A variable written without an `=` (e.g. `[core] bare`) has no value.
+    pub fn has_value(&self) -> bool {
+        unsafe { !(*self.raw).value.is_null() }
+    }
+
     /// Gets the configuration level of this entry.
     pub fn level(&self) -> ConfigLevel {
         unsafe { ConfigLevel::from_raw((*self.raw).level) }
@@ -506,9 +531,10 @@ impl<'cfg> Drop for ConfigEntry<'cfg> {
 #[cfg(test)]
 mod tests {
     use std::fs::File;
+    use std::io::prelude::*;
     use tempdir::TempDir;
 
-    use Config;
+    use {Config, ConfigLevel, ErrorCode};
 
     #[test]
     fn smoke() {
@@ -547,6 +573,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn entry_level() {
+        let (_td, repo) = ::test::repo_init();
+        let mut cfg = repo.config().unwrap();
+        cfg.set_str("foo.bar", "baz").unwrap();
+
+        let cfg = repo.config().unwrap().snapshot().unwrap();
+        let entry = cfg.get_entry("foo.bar").unwrap();
+        assert_eq!(entry.name(), Some("foo.bar"));
+        assert!(entry.has_value());
+        assert_eq!(entry.value(), Some("baz"));
+        assert_eq!(entry.value_bytes(), b"baz");
+        assert_eq!(entry.level(), ConfigLevel::Local);
+        assert!(cfg.get_entry("foo.missing").is_err());
+    }
+
+    #[test]
+    fn entry_without_value() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("foo");
+        File::create(&path).unwrap().write_all(b"[foo]\n\tbar\n").unwrap();
+
+        let cfg = Config::open(&path).unwrap();
+        let entry = cfg.get_entry("foo.bar").unwrap();
+        assert!(!entry.has_value());
+        assert_eq!(cfg.get_bool("foo.bar").unwrap(), true);
+    }
+
     #[test]
     fn multivar() {
         let td = TempDir::new("test").unwrap();
@@ -564,6 +618,33 @@ mod tests {
             .collect();
         values.sort();
         assert_eq!(values, ["baz", "qux"]);
+
+        cfg.remove_multivar("foo.bar", "^b").unwrap();
+        let values: Vec<String> = cfg.entries(None)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.unwrap().value().unwrap().into())
+            .collect();
+        assert_eq!(values, ["qux"]);
+
+        cfg.remove_multivar("foo.bar", ".*").unwrap();
+        assert_eq!(cfg.entries(None).unwrap().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn remove() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("foo");
+        File::create(&path).unwrap();
+
+        let mut cfg = Config::open(&path).unwrap();
+        cfg.set_str("user.name", "foo").unwrap();
+        assert_eq!(cfg.get_string("user.name").unwrap(), "foo");
+        cfg.remove("user.name").unwrap();
+        assert!(cfg.get_string("user.name").is_err());
+
+        let err = cfg.remove("user.name").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::NotFound);
     }
 
     #[test]
@@ -583,6 +664,9 @@ mod tests {
         assert!(Config::parse_bool(" ").is_err());
         assert!(Config::parse_bool("some-string").is_err());
         assert!(Config::parse_bool("-").is_err());
+        assert_eq!(Config::parse_bool("TRUE").unwrap(), true);
+        assert_eq!(Config::parse_bool("Yes").unwrap(), true);
+        assert_eq!(Config::parse_bool("OFF").unwrap(), false);
 
         assert_eq!(Config::parse_i32("0").unwrap(), 0);
         assert_eq!(Config::parse_i32("1").unwrap(), 1);
@@ -593,6 +677,10 @@ mod tests {
         assert_eq!(Config::parse_i32("4k").unwrap(), 4096);
         assert_eq!(Config::parse_i32("1M").unwrap(), 1048576);
         assert_eq!(Config::parse_i32("1G").unwrap(), 1024*1024*1024);
+        assert_eq!(Config::parse_i32("2m").unwrap(), 2*1024*1024);
+        assert!(Config::parse_i32("2G").is_err());
+        assert!(Config::parse_i32("1x").is_err());
+        assert!(Config::parse_i32("k").is_err());
 
         assert_eq!(Config::parse_i64("0").unwrap(), 0);
         assert_eq!(Config::parse_i64("1").unwrap(), 1);
@@ -604,5 +692,7 @@ mod tests {
         assert_eq!(Config::parse_i64("1M").unwrap(), 1048576);
         assert_eq!(Config::parse_i64("1G").unwrap(), 1024*1024*1024);
         assert_eq!(Config::parse_i64("100G").unwrap(), 100*1024*1024*1024);
+        assert_eq!(Config::parse_i64("2g").unwrap(), 2*1024*1024*1024);
+        assert!(Config::parse_i64("1x").is_err());
     }
 }
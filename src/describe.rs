@@ -153,7 +153,8 @@ impl DescribeOptions {
         self
     }
 
-    #[allow(missing_docs)]
+    /// Only consider tags or references matching the given glob pattern as
+    /// candidates, like the `--match` option to git-describe.
     pub fn pattern(&mut self, pattern: &str) -> &mut Self {
         self.pattern = CString::new(pattern).unwrap();
         self.raw.pattern = self.pattern.as_ptr();
@@ -196,4 +197,32 @@ mod tests {
         let d = t!(obj.describe(&DescribeOptions::new()));
         assert_eq!(t!(d.format(None)), "foo");
     }
+
+    #[test]
+    fn pattern() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let first = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.tag("v1.0", first.as_object(), &sig, "release", false));
+
+        let tree = t!(first.tree());
+        let id = t!(repo.commit(Some("HEAD"), &sig, &sig, "second", &tree,
+                                &[&first]));
+        let second = t!(repo.find_object(id, None));
+        t!(repo.tag("rc-3", &second, &sig, "candidate", false));
+
+        let d = t!(repo.describe(&DescribeOptions::new()));
+        assert_eq!(t!(d.format(None)), "rc-3");
+
+        let d = t!(repo.describe(DescribeOptions::new().pattern("v*")));
+        let expected = format!("v1.0-1-g{}", &id.to_string()[..7]);
+        assert_eq!(t!(d.format(None)), expected);
+
+        let mut opts = DescribeOptions::new();
+        opts.pattern("nomatch*");
+        assert!(repo.describe(&opts).is_err());
+        opts.show_commit_oid_as_fallback(true);
+        let d = t!(repo.describe(&opts));
+        assert_eq!(t!(d.format(None)), &id.to_string()[..7]);
+    }
 }
@@ -1,4 +1,4 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::marker;
 use std::mem;
 use std::ops::Range;
@@ -7,8 +7,9 @@ use std::ptr;
 use std::slice;
 use libc::{c_char, size_t, c_void, c_int};
 
-use {raw, panic, Buf, Delta, Oid, Repository, Error, DiffFormat};
-use {DiffStatsFormat, IntoCString};
+use {raw, panic, Blob, Buf, Delta, Oid, Repository, Error, DiffFormat};
+use {DiffFlags, DiffNotifyAction, DiffOptionFlags, DiffStatsFormat};
+use IntoCString;
 use util::{self, Binding};
 
 /// The diff object that contains all individual file deltas.
@@ -40,11 +41,13 @@ pub struct DiffFile<'a> {
 }
 
 /// Structure describing options about how the diff should be executed.
-pub struct DiffOptions {
+pub struct DiffOptions<'cb> {
     pathspec: Vec<CString>,
     pathspec_ptrs: Vec<*const c_char>,
     old_prefix: Option<CString>,
     new_prefix: Option<CString>,
+    notify: Option<Box<DiffNotify<'cb>>>,
+    progress: Option<Box<DiffProgress<'cb>>>,
     raw: raw::git_diff_options,
 }
 
@@ -92,7 +95,7 @@ pub struct DiffBinaryFile<'a> {
 /// either the deflated full ("literal") contents of the file, or
 /// the deflated binary delta between the two sides (whichever is
 /// smaller).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DiffBinaryKind {
     /// There is no binary delta
     None,
@@ -109,8 +112,21 @@ pub type BinaryCb<'a> = FnMut(DiffDelta, DiffBinary) -> bool + 'a;
 pub type HunkCb<'a> = FnMut(DiffDelta, DiffHunk) -> bool + 'a;
 pub type LineCb<'a> = FnMut(DiffDelta, Option<DiffHunk>, DiffLine) -> bool + 'a;
 
+/// Diff notification callback.
+///
+/// The first argument is the delta about to be added to the diff, and the
+/// second is the pathspec which matched it, if any.
+pub type DiffNotify<'a> = FnMut(DiffDelta, Option<&[u8]>) -> DiffNotifyAction
+                          + 'a;
+
+/// Diff progress callback.
+///
+/// The arguments are the old and new paths of the file being processed. The
+/// callback must return a bool specifying whether the diff should continue.
+pub type DiffProgress<'a> = FnMut(Option<&Path>, Option<&Path>) -> bool + 'a;
+
 struct ForeachCallbacks<'a, 'b: 'a, 'c, 'd: 'c, 'e, 'f: 'e, 'g, 'h: 'g> {
-    file: &'a mut FileCb<'b>,
+    file: Option<&'a mut FileCb<'b>>,
     binary: Option<&'c mut BinaryCb<'d>>,
     hunk: Option<&'e mut HunkCb<'f>>,
     line: Option<&'g mut LineCb<'h>>,
@@ -176,7 +192,7 @@ impl<'repo> Diff<'repo> {
                    hunk_cb: Option<&mut HunkCb>,
                    line_cb: Option<&mut LineCb>) -> Result<(), Error> {
         let mut cbs = ForeachCallbacks {
-            file: file_cb,
+            file: Some(file_cb),
             binary: binary_cb,
             hunk: hunk_cb,
             line: line_cb,
@@ -205,6 +221,65 @@ impl<'repo> Diff<'repo> {
         }
     }
 
+    /// Directly run a diff on two blobs, issuing callbacks for each change.
+    ///
+    /// This does not require a repository and does not construct a `Diff`.
+    /// Passing `None` for either blob treats it as an empty file, and the
+    /// paths are used only as the file names reported to the callbacks.
+    ///
+    /// Returning `false` from any callback will terminate the iteration and
+    /// return an error from this function.
+    pub fn blobs(old_blob: Option<&Blob>,
+                 old_as_path: Option<&str>,
+                 new_blob: Option<&Blob>,
+                 new_as_path: Option<&str>,
+                 opts: Option<&mut DiffOptions>,
+                 file_cb: Option<&mut FileCb>,
+                 binary_cb: Option<&mut BinaryCb>,
+                 hunk_cb: Option<&mut HunkCb>,
+                 line_cb: Option<&mut LineCb>) -> Result<(), Error> {
+        let old_as_path = try!(::opt_cstr(old_as_path));
+        let new_as_path = try!(::opt_cstr(new_as_path));
+        let mut cbs = ForeachCallbacks {
+            file: file_cb,
+            binary: binary_cb,
+            hunk: hunk_cb,
+            line: line_cb,
+        };
+        let ptr = &mut cbs as *mut _;
+        unsafe {
+            let file_cb_c = if cbs.file.is_some() {
+                Some(file_cb_c as raw::git_diff_file_cb)
+            } else {
+                None
+            };
+            let binary_cb_c = if cbs.binary.is_some() {
+                Some(binary_cb_c as raw::git_diff_binary_cb)
+            } else {
+                None
+            };
+            let hunk_cb_c = if cbs.hunk.is_some() {
+                Some(hunk_cb_c as raw::git_diff_hunk_cb)
+            } else {
+                None
+            };
+            let line_cb_c = if cbs.line.is_some() {
+                Some(line_cb_c as raw::git_diff_line_cb)
+            } else {
+                None
+            };
+            try_call!(raw::git_diff_blobs(old_blob.map(|s| s.raw()),
+                                          old_as_path,
+                                          new_blob.map(|s| s.raw()),
+                                          new_as_path,
+                                          opts.map(|s| s.raw()),
+                                          file_cb_c, binary_cb_c,
+                                          hunk_cb_c, line_cb_c,
+                                          ptr as *mut _));
+            Ok(())
+        }
+    }
+
     /// Accumulate diff statistics for all patches.
     pub fn stats(&self) -> Result<DiffStats, Error> {
         let mut ret = ptr::null_mut();
@@ -255,7 +330,10 @@ extern fn file_cb_c(delta: *const raw::git_diff_delta,
 
         let r = panic::wrap(|| {
             let cbs = data as *mut ForeachCallbacks;
-            ((*cbs).file)(delta, progress)
+            match (*cbs).file {
+                Some(ref mut cb) => cb(delta, progress),
+                None => false,
+            }
         });
         if r == Some(true) {0} else {-1}
     }
@@ -336,10 +414,20 @@ impl<'repo> Drop for Diff<'repo> {
 }
 
 impl<'a> DiffDelta<'a> {
-    // TODO: expose when diffs are more exposed
-    // pub fn similarity(&self) -> u16 {
-    //     unsafe { (*self.raw).similarity }
-    // }
+    /// Returns the flags on the delta.
+    ///
+    /// For more information, see `DiffFlags`'s documentation.
+    pub fn flags(&self) -> DiffFlags {
+        DiffFlags::from_bits_truncate(unsafe { (*self.raw).flags })
+    }
+
+    /// Returns the similarity score of this delta, between 0 and 100.
+    ///
+    /// This is only filled in for renamed and copied deltas once
+    /// `Diff::find_similar` has been run.
+    pub fn similarity(&self) -> u16 {
+        unsafe { (*self.raw).similarity }
+    }
 
     /// Returns the number of files in this delta.
     pub fn nfiles(&self) -> u16 {
@@ -419,7 +507,18 @@ impl<'a> DiffFile<'a> {
     /// Returns the size of this entry, in bytes
     pub fn size(&self) -> u64 { unsafe { (*self.raw).size as u64 } }
 
-    // TODO: expose flags/mode
+    /// Returns the flags on this side of the delta.
+    pub fn flags(&self) -> DiffFlags {
+        DiffFlags::from_bits_truncate(unsafe { (*self.raw).flags })
+    }
+
+    /// Returns the file mode of this entry, or 0 if it doesn't exist.
+    pub fn mode(&self) -> u16 { unsafe { (*self.raw).mode } }
+
+    /// Returns `true` if the file exists at this side of the delta.
+    pub fn exists(&self) -> bool {
+        self.flags().contains(::DIFF_FLAG_EXISTS)
+    }
 }
 
 impl<'a> Binding for DiffFile<'a> {
@@ -433,24 +532,26 @@ impl<'a> Binding for DiffFile<'a> {
     fn raw(&self) -> *const raw::git_diff_file { self.raw }
 }
 
-impl Default for DiffOptions {
+impl<'cb> Default for DiffOptions<'cb> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl DiffOptions {
+impl<'cb> DiffOptions<'cb> {
     /// Creates a new set of empty diff options.
     ///
     /// All flags and other options are defaulted to false or their otherwise
     /// zero equivalents.
-    pub fn new() -> DiffOptions {
+    pub fn new() -> DiffOptions<'cb> {
         let mut opts = DiffOptions {
             pathspec: Vec::new(),
             pathspec_ptrs: Vec::new(),
             raw: unsafe { mem::zeroed() },
             old_prefix: None,
             new_prefix: None,
+            notify: None,
+            progress: None,
         };
         assert_eq!(unsafe {
             raw::git_diff_init_options(&mut opts.raw, 1)
@@ -458,7 +559,7 @@ impl DiffOptions {
         opts
     }
 
-    fn flag(&mut self, opt: u32, val: bool) -> &mut DiffOptions {
+    fn flag(&mut self, opt: u32, val: bool) -> &mut DiffOptions<'cb> {
         if val {
             self.raw.flags |= opt;
         } else {
@@ -467,39 +568,54 @@ impl DiffOptions {
         self
     }
 
+    /// Enable each of the given flags.
+    ///
+    /// This is equivalent to calling the individual setters, such as
+    /// `reverse` or `patience`, with `true`. Flags which are not given are
+    /// left unchanged.
+    pub fn flags(&mut self, flags: DiffOptionFlags) -> &mut DiffOptions<'cb> {
+        self.raw.flags |= flags.bits();
+        self
+    }
+
     /// Flag indicating whether the sides of the diff will be reversed.
-    pub fn reverse(&mut self, reverse: bool) -> &mut DiffOptions {
+    pub fn reverse(&mut self, reverse: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_REVERSE, reverse)
     }
 
     /// Flag indicating whether ignored files are included.
-    pub fn include_ignored(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_ignored(&mut self, include: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_IGNORED, include)
     }
 
     /// Flag indicating whether ignored directories are traversed deeply or not.
-    pub fn recurse_ignored_dirs(&mut self, recurse: bool) -> &mut DiffOptions {
+    pub fn recurse_ignored_dirs(&mut self, recurse: bool)
+                                -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_RECURSE_IGNORED_DIRS, recurse)
     }
 
     /// Flag indicating whether untracked files are in the diff
-    pub fn include_untracked(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_untracked(&mut self, include: bool)
+                             -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_UNTRACKED, include)
     }
 
     /// Flag indicating whether untracked directories are deeply traversed or
     /// not.
-    pub fn recurse_untracked_dirs(&mut self, recurse: bool) -> &mut DiffOptions {
+    pub fn recurse_untracked_dirs(&mut self, recurse: bool)
+                                  -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_RECURSE_UNTRACKED_DIRS, recurse)
     }
 
     /// Flag indicating whether unmodified files are in the diff.
-    pub fn include_unmodified(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_unmodified(&mut self, include: bool)
+                              -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_UNMODIFIED, include)
     }
 
     /// If entrabled, then Typechange delta records are generated.
-    pub fn include_typechange(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_typechange(&mut self, include: bool)
+                              -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_TYPECHANGE, include)
     }
 
@@ -508,35 +624,37 @@ impl DiffOptions {
     /// typechange record with the `new_file`'s mode set to tree.
     ///
     /// Note that the tree SHA will not be available.
-    pub fn include_typechange_trees(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_typechange_trees(&mut self, include: bool)
+                                    -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_TYPECHANGE_TREES, include)
     }
 
     /// Flag indicating whether file mode changes are ignored.
-    pub fn ignore_filemode(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_filemode(&mut self, ignore: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_FILEMODE, ignore)
     }
 
     /// Flag indicating whether all submodules should be treated as unmodified.
-    pub fn ignore_submodules(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_submodules(&mut self, ignore: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_SUBMODULES, ignore)
     }
 
     /// Flag indicating whether case insensitive filenames should be used.
-    pub fn ignore_case(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_case(&mut self, ignore: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_CASE, ignore)
     }
 
     /// If pathspecs are specified, this flag means that they should be applied
     /// as an exact match instead of a fnmatch pattern.
-    pub fn disable_pathspec_match(&mut self, disable: bool) -> &mut DiffOptions {
+    pub fn disable_pathspec_match(&mut self, disable: bool)
+                                  -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_DISABLE_PATHSPEC_MATCH, disable)
     }
 
     /// Disable updating the `binary` flag in delta records. This is useful when
     /// iterating over a diff if you don't need hunk and data callbacks and want
     /// to avoid having to load a file completely.
-    pub fn skip_binary_check(&mut self, skip: bool) -> &mut DiffOptions {
+    pub fn skip_binary_check(&mut self, skip: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_SKIP_BINARY_CHECK, skip)
     }
 
@@ -549,7 +667,7 @@ impl DiffOptions {
     /// This flag turns off that scan and immediately labels an untracked
     /// directory as untracked (changing the behavior to not match core git).
     pub fn enable_fast_untracked_dirs(&mut self, enable: bool)
-                                      -> &mut DiffOptions {
+                                      -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_ENABLE_FAST_UNTRACKED_DIRS, enable)
     }
 
@@ -557,43 +675,46 @@ impl DiffOptions {
     /// different from the index, but the OID ends up being the same, write the
     /// correct stat information into the index. Note: without this flag, diff
     /// will always leave the index untouched.
-    pub fn update_index(&mut self, update: bool) -> &mut DiffOptions {
+    pub fn update_index(&mut self, update: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_UPDATE_INDEX, update)
     }
 
     /// Include unreadable files in the diff
-    pub fn include_unreadable(&mut self, include: bool) -> &mut DiffOptions {
+    pub fn include_unreadable(&mut self, include: bool)
+                              -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_UNREADABLE, include)
     }
 
     /// Include unreadable files in the diff
     pub fn include_unreadable_as_untracked(&mut self, include: bool)
-                                           -> &mut DiffOptions {
+                                           -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_INCLUDE_UNREADABLE_AS_UNTRACKED, include)
     }
 
     /// Treat all files as text, disabling binary attributes and detection.
-    pub fn force_text(&mut self, force: bool) -> &mut DiffOptions {
+    pub fn force_text(&mut self, force: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_FORCE_TEXT, force)
     }
 
     /// Treat all files as binary, disabling text diffs
-    pub fn force_binary(&mut self, force: bool) -> &mut DiffOptions {
+    pub fn force_binary(&mut self, force: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_FORCE_TEXT, force)
     }
 
     /// Ignore all whitespace
-    pub fn ignore_whitespace(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_whitespace(&mut self, ignore: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_WHITESPACE, ignore)
     }
 
     /// Ignore changes in the amount of whitespace
-    pub fn ignore_whitespace_change(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_whitespace_change(&mut self, ignore: bool)
+                                    -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_WHITESPACE_CHANGE, ignore)
     }
 
     /// Ignore whitespace at tend of line
-    pub fn ignore_whitespace_eol(&mut self, ignore: bool) -> &mut DiffOptions {
+    pub fn ignore_whitespace_eol(&mut self, ignore: bool)
+                                 -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_IGNORE_WHITESPACE_EOL, ignore)
     }
 
@@ -602,7 +723,8 @@ impl DiffOptions {
     /// This automatically turns on `include_untracked` but it does not turn on
     /// `recurse_untracked_dirs`. Add that flag if you want the content of every
     /// single untracked file.
-    pub fn show_untracked_content(&mut self, show: bool) -> &mut DiffOptions {
+    pub fn show_untracked_content(&mut self, show: bool)
+                                  -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_SHOW_UNTRACKED_CONTENT, show)
     }
 
@@ -610,31 +732,32 @@ impl DiffOptions {
     /// are included in the `Diff`. Normally these are skipped in the formats
     /// that list files (e.g. name-only, name-status, raw). Even with this these
     /// will not be included in the patch format.
-    pub fn show_unmodified(&mut self, show: bool) -> &mut DiffOptions {
+    pub fn show_unmodified(&mut self, show: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_SHOW_UNMODIFIED, show)
     }
 
     /// Use the "patience diff" algorithm
-    pub fn patience(&mut self, patience: bool) -> &mut DiffOptions {
+    pub fn patience(&mut self, patience: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_PATIENCE, patience)
     }
 
     /// Take extra time to find the minimal diff
-    pub fn minimal(&mut self, minimal: bool) -> &mut DiffOptions {
+    pub fn minimal(&mut self, minimal: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_MINIMAL, minimal)
     }
 
     /// Include the necessary deflate/delta information so that `git-apply` can
     /// apply given diff information to binary files.
-    pub fn show_binary(&mut self, show: bool) -> &mut DiffOptions {
+    pub fn show_binary(&mut self, show: bool) -> &mut DiffOptions<'cb> {
         self.flag(raw::GIT_DIFF_SHOW_BINARY, show)
     }
 
     /// Set the number of unchanged lines that define the boundary of a hunk
     /// (and to display before and after).
     ///
-    /// The default value for this is 3.
-    pub fn context_lines(&mut self, lines: u32) -> &mut DiffOptions {
+    /// The default value for this is 3. A value of 0 produces hunks with no
+    /// surrounding context, like `git diff -U0`.
+    pub fn context_lines(&mut self, lines: u32) -> &mut DiffOptions<'cb> {
         self.raw.context_lines = lines;
         self
     }
@@ -643,13 +766,17 @@ impl DiffOptions {
     /// the hunks will be merged into one.
     ///
     /// The default value for this is 0.
-    pub fn interhunk_lines(&mut self, lines: u32) -> &mut DiffOptions {
+    pub fn interhunk_lines(&mut self, lines: u32) -> &mut DiffOptions<'cb> {
         self.raw.interhunk_lines = lines;
         self
     }
 
-    /// The default value for this is `core.abbrev` or 7 if unset.
-    pub fn id_abbrev(&mut self, abbrev: u16) -> &mut DiffOptions {
+    /// Set the number of hex digits used when abbreviating object ids, such
+    /// as in the `index` line of a patch or in `DiffFormat::Raw` output.
+    ///
+    /// Setting this to 0 uses the default, which is `core.abbrev` or 7 if
+    /// unset. Values above 40 show the full id.
+    pub fn id_abbrev(&mut self, abbrev: u16) -> &mut DiffOptions<'cb> {
         self.raw.id_abbrev = abbrev;
         self
     }
@@ -660,7 +787,7 @@ impl DiffOptions {
     /// A negative value will disable this entirely.
     ///
     /// The default value for this is 512MB.
-    pub fn max_size(&mut self, size: i64) -> &mut DiffOptions {
+    pub fn max_size(&mut self, size: i64) -> &mut DiffOptions<'cb> {
         self.raw.max_size = size as raw::git_off_t;
         self
     }
@@ -668,7 +795,8 @@ impl DiffOptions {
     /// The virtual "directory" to prefix old file names with in hunk headers.
     ///
     /// The default value for this is "a".
-    pub fn old_prefix<T: IntoCString>(&mut self, t: T) -> &mut DiffOptions {
+    pub fn old_prefix<T: IntoCString>(&mut self, t: T)
+                                      -> &mut DiffOptions<'cb> {
         self.old_prefix = Some(t.into_c_string().unwrap());
         self
     }
@@ -676,14 +804,18 @@ impl DiffOptions {
     /// The virtual "directory" to prefix new file names with in hunk headers.
     ///
     /// The default value for this is "b".
-    pub fn new_prefix<T: IntoCString>(&mut self, t: T) -> &mut DiffOptions {
+    pub fn new_prefix<T: IntoCString>(&mut self, t: T)
+                                      -> &mut DiffOptions<'cb> {
         self.new_prefix = Some(t.into_c_string().unwrap());
         self
     }
 
     /// Add to the array of paths/fnmatch patterns to constrain the diff.
+    ///
+    /// This may be called repeatedly; a path is included in the diff if it
+    /// matches any of the patterns.
     pub fn pathspec<T: IntoCString>(&mut self, pathspec: T)
-                                       -> &mut DiffOptions {
+                                       -> &mut DiffOptions<'cb> {
         let s = pathspec.into_c_string().unwrap();
         self.pathspec_ptrs.push(s.as_ptr());
         self.pathspec.push(s);
@@ -701,10 +833,92 @@ impl DiffOptions {
                                   .unwrap_or(ptr::null());
         self.raw.pathspec.count = self.pathspec_ptrs.len() as size_t;
         self.raw.pathspec.strings = self.pathspec_ptrs.as_ptr() as *mut _;
+        self.raw.notify_cb = None;
+        self.raw.progress_cb = None;
+        if self.notify.is_some() {
+            let f: raw::git_diff_notify_cb = notify_cb;
+            self.raw.notify_cb = Some(f);
+        }
+        if self.progress.is_some() {
+            let f: raw::git_diff_progress_cb = progress_cb;
+            self.raw.progress_cb = Some(f);
+        }
+        self.raw.payload = self as *mut _ as *mut c_void;
         &self.raw as *const _
     }
 
-    // TODO: expose ignore_submodules, notify_cb/notify_payload
+    /// Set a callback to be invoked as each delta is added to the diff.
+    ///
+    /// The callback decides whether the delta is kept, left out of the diff,
+    /// or whether diff generation stops altogether. Aborting makes the diff
+    /// function fail with `ErrorCode::User`.
+    pub fn notify<F>(&mut self, cb: F) -> &mut DiffOptions<'cb>
+        where F: FnMut(DiffDelta, Option<&[u8]>) -> DiffNotifyAction + 'cb
+    {
+        self.notify = Some(Box::new(cb) as Box<DiffNotify<'cb>>);
+        self
+    }
+
+    /// Set a callback to receive the old and new paths of each file as the
+    /// diff is generated.
+    ///
+    /// Returning `false` from the callback will cancel the diff, which then
+    /// fails with `ErrorCode::User`.
+    pub fn progress<F>(&mut self, cb: F) -> &mut DiffOptions<'cb>
+        where F: FnMut(Option<&Path>, Option<&Path>) -> bool + 'cb
+    {
+        self.progress = Some(Box::new(cb) as Box<DiffProgress<'cb>>);
+        self
+    }
+
+    // TODO: expose ignore_submodules
+}
+
+extern fn notify_cb(_diff: *const raw::git_diff,
+                    delta: *const raw::git_diff_delta,
+                    matched_pathspec: *const c_char,
+                    data: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let payload = &mut *(data as *mut DiffOptions);
+        let callback = match payload.notify {
+            Some(ref mut c) => c,
+            None => return 0,
+        };
+        let pathspec = if matched_pathspec.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(matched_pathspec).to_bytes())
+        };
+        match callback(Binding::from_raw(delta as *mut _), pathspec) {
+            DiffNotifyAction::Include => 0,
+            DiffNotifyAction::Skip => 1,
+            DiffNotifyAction::Abort => raw::GIT_EUSER,
+        }
+    }).unwrap_or(-1)
+}
+
+extern fn progress_cb(_diff: *const raw::git_diff,
+                      old_path: *const c_char,
+                      new_path: *const c_char,
+                      data: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let payload = &mut *(data as *mut DiffOptions);
+        let callback = match payload.progress {
+            Some(ref mut c) => c,
+            None => return 0,
+        };
+        let old_path = if old_path.is_null() {
+            None
+        } else {
+            Some(util::bytes2path(CStr::from_ptr(old_path).to_bytes()))
+        };
+        let new_path = if new_path.is_null() {
+            None
+        } else {
+            Some(util::bytes2path(CStr::from_ptr(new_path).to_bytes()))
+        };
+        if callback(old_path, new_path) {0} else {raw::GIT_EUSER}
+    }).unwrap_or(-1)
 }
 
 impl<'diff> Iterator for Deltas<'diff> {
@@ -1138,7 +1352,9 @@ impl DiffFindOptions {
 
 #[cfg(test)]
 mod tests {
-    use DiffOptions;
+    use {Delta, Diff, DiffBinaryKind, DiffFindOptions, DiffOptions};
+    use {DiffNotifyAction, ErrorCode};
+    use {Index, IndexEntry, IndexTime};
     use std::fs::File;
     use std::path::Path;
     use std::borrow::Borrow;
@@ -1247,4 +1463,328 @@ mod tests {
         assert_eq!(new_lines, 1);
         assert_eq!(line_content, Some("bar\n".to_string()));
     }
+
+    #[test]
+    fn diff_blobs() {
+        let (_td, repo) = ::test::repo_init();
+        let old = t!(repo.find_blob(t!(repo.blob(b"a\nb\n"))));
+        let new = t!(repo.find_blob(t!(repo.blob(b"a\nc\n"))));
+
+        let mut files = Vec::new();
+        let mut lines = Vec::new();
+        t!(Diff::blobs(Some(&old), Some("foo"), Some(&new), Some("foo"), None,
+                       Some(&mut |delta, _progress| {
+                           files.push(delta.new_file().path()
+                                           .map(|p| p.to_path_buf()));
+                           true
+                       }),
+                       None,
+                       None,
+                       Some(&mut |_delta, _hunk, line| {
+                           let content = line.content().to_vec();
+                           lines.push((line.origin(),
+                                       String::from_utf8(content).unwrap()));
+                           true
+                       })));
+        assert_eq!(files, vec![Some(Path::new("foo").to_path_buf())]);
+        assert_eq!(lines, vec![(' ', "a\n".to_string()),
+                               ('-', "b\n".to_string()),
+                               ('+', "c\n".to_string())]);
+
+        let mut added = Vec::new();
+        t!(Diff::blobs(None, None, Some(&new), None, None, None, None, None,
+                       Some(&mut |_delta, _hunk, line| {
+                           added.push(line.origin());
+                           true
+                       })));
+        assert_eq!(added, vec!['+', '+']);
+    }
+
+    #[test]
+    fn head_tree_to_index() {
+        let (td, repo) = ::test::repo_init();
+        let staged = Path::new("staged");
+        t!(t!(File::create(&td.path().join(staged))).write_all(b"index\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(staged));
+        t!(index.write());
+        t!(t!(File::create(&td.path().join(staged))).write_all(b"workdir\n"));
+        t!(t!(File::create(&td.path().join("unstaged"))).write_all(b"foo\n"));
+
+        let head = t!(t!(repo.head()).peel_to_tree());
+        let diff = t!(repo.diff_tree_to_index(Some(&head), None, None));
+        assert_eq!(diff.deltas().len(), 1);
+        let delta = diff.deltas().next().unwrap();
+        assert_eq!(delta.status(), Delta::Added);
+        assert_eq!(delta.new_file().path(), Some(staged));
+
+        let mut lines = Vec::new();
+        t!(diff.foreach(&mut |_file, _progress| true, None, None,
+                        Some(&mut |_file, _hunk, line| {
+                            lines.push(line.content().to_vec());
+                            true
+                        })));
+        assert_eq!(lines, vec![b"index\n".to_vec()]);
+    }
+
+    #[test]
+    fn rename_similarity() {
+        let (_td, repo) = ::test::repo_init();
+        let content = b"line 1\nline 2\nline 3\nline 4\nline 5\nline 6\n";
+        let blob = t!(repo.blob(content));
+        let old = {
+            let mut builder = t!(repo.treebuilder(None));
+            t!(builder.insert("foo", blob, 0o100644));
+            t!(repo.find_tree(t!(builder.write())))
+        };
+        let new = {
+            let mut builder = t!(repo.treebuilder(None));
+            t!(builder.insert("bar", blob, 0o100644));
+            t!(repo.find_tree(t!(builder.write())))
+        };
+
+        let mut diff = t!(repo.diff_tree_to_tree(Some(&old), Some(&new), None));
+        assert_eq!(diff.deltas().len(), 2);
+        t!(diff.find_similar(Some(DiffFindOptions::new().renames(true))));
+        assert_eq!(diff.deltas().len(), 1);
+
+        let delta = diff.deltas().next().unwrap();
+        assert_eq!(delta.status(), Delta::Renamed);
+        assert!(delta.similarity() > 50);
+        assert_eq!(delta.nfiles(), 2);
+        assert_eq!(delta.old_file().path(), Some(Path::new("foo")));
+        assert_eq!(delta.new_file().path(), Some(Path::new("bar")));
+        assert!(delta.old_file().exists());
+        assert!(delta.new_file().exists());
+        assert_eq!(delta.new_file().mode(), 0o100644);
+        assert!(delta.new_file().flags().contains(::DIFF_FLAG_VALID_ID));
+    }
+
+    #[test]
+    fn hunk_header() {
+        let (_td, repo) = ::test::repo_init();
+        let old = t!(repo.find_blob(t!(repo.blob(b"a\nb\nc\n"))));
+        let new = t!(repo.find_blob(t!(repo.blob(b"a\nb\nx\nc\n"))));
+
+        let mut hunks = Vec::new();
+        t!(Diff::blobs(Some(&old), None, Some(&new), None, None, None, None,
+                       Some(&mut |_delta, hunk| {
+                           hunks.push((hunk.old_start(), hunk.old_lines(),
+                                       hunk.new_start(), hunk.new_lines(),
+                                       hunk.header().to_vec()));
+                           true
+                       }),
+                       None));
+        assert_eq!(hunks.len(), 1);
+        let (old_start, old_lines, new_start, new_lines, ref header) = hunks[0];
+        assert_eq!((old_start, new_start), (1, 1));
+        assert_eq!(new_lines, old_lines + 1);
+        assert_eq!(&header[..], &b"@@ -1,3 +1,4 @@\n"[..]);
+    }
+
+    #[test]
+    fn binary_blobs() {
+        let (_td, repo) = ::test::repo_init();
+        let old = t!(repo.find_blob(t!(repo.blob(&[0, 1, 2, 0]))));
+        let new = t!(repo.find_blob(t!(repo.blob(&[0, 1, 2, 3, 0]))));
+
+        let mut opts = DiffOptions::new();
+        opts.show_binary(true);
+        let mut calls = 0;
+        let mut lines = 0;
+        t!(Diff::blobs(Some(&old), None, Some(&new), None, Some(&mut opts),
+                       None,
+                       Some(&mut |delta, binary| {
+                           calls += 1;
+                           assert!(delta.flags().contains(::DIFF_FLAG_BINARY));
+                           assert!(binary.contains_data());
+                           assert!(binary.new_file().kind() != DiffBinaryKind::None);
+                           assert!(binary.new_file().data().len() > 0);
+                           assert!(binary.new_file().inflated_len() > 0);
+                           true
+                       }),
+                       None,
+                       Some(&mut |_delta, _hunk, _line| { lines += 1; true })));
+        assert_eq!(calls, 1);
+        assert_eq!(lines, 0);
+    }
+
+    #[test]
+    fn indexed_deltas() {
+        let (td, repo) = ::test::repo_init();
+        for name in &["c", "a", "b"] {
+            t!(t!(File::create(&td.path().join(name))).write_all(b"foo\n"));
+        }
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true);
+        let diff = t!(repo.diff_tree_to_workdir(None, Some(&mut opts)));
+
+        let deltas = diff.deltas();
+        assert_eq!(deltas.len(), 3);
+        let paths = deltas.map(|d| d.new_file().path().unwrap().to_path_buf())
+                          .collect::<Vec<_>>();
+        assert_eq!(paths, [Path::new("a"), Path::new("b"), Path::new("c")]);
+        for (i, path) in paths.iter().enumerate() {
+            let delta = diff.get_delta(i).unwrap();
+            assert_eq!(delta.new_file().path(), Some(path.as_path()));
+            assert_eq!(delta.status(), Delta::Untracked);
+        }
+        assert!(diff.get_delta(3).is_none());
+        assert_eq!(diff.deltas().rev().next().unwrap().new_file().path(),
+                   Some(Path::new("c")));
+        let _ = diff.is_sorted_icase();
+    }
+
+    #[test]
+    fn index_to_index() {
+        let (_td, repo) = ::test::repo_init();
+        let entry = |path: &str, contents: &[u8]| {
+            IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: contents.len() as u32,
+                id: repo.blob(contents).unwrap(),
+                flags: 0,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            }
+        };
+
+        let mut old_index = Index::new().unwrap();
+        old_index.add(&entry("same", b"same\n")).unwrap();
+        old_index.add(&entry("changed", b"old\n")).unwrap();
+        let mut new_index = Index::new().unwrap();
+        new_index.add(&entry("same", b"same\n")).unwrap();
+        new_index.add(&entry("changed", b"new\n")).unwrap();
+
+        let diff = repo.diff_index_to_index(&old_index, &new_index, None)
+                       .unwrap();
+        assert_eq!(diff.deltas().len(), 1);
+        let delta = diff.get_delta(0).unwrap();
+        assert_eq!(delta.status(), Delta::Modified);
+        assert_eq!(delta.new_file().path(), Some(Path::new("changed")));
+
+        new_index.add(&entry("added", b"added\n")).unwrap();
+        let diff = repo.diff_index_to_index(&old_index, &new_index, None)
+                       .unwrap();
+        let statuses = diff.deltas().map(|d| d.status()).collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Delta::Added, Delta::Modified]);
+    }
+
+    #[test]
+    fn pathspec_and_flags() {
+        let (td, repo) = ::test::repo_init();
+        t!(::std::fs::create_dir_all(td.path().join("src/nested")));
+        t!(::std::fs::create_dir_all(td.path().join("docs")));
+        let files = ["src/lib.rs", "src/nested/mod.rs", "docs/README", "top"];
+        for path in files.iter() {
+            t!(t!(File::create(&td.path().join(path))).write_all(b"text\n"));
+        }
+
+        let mut opts = DiffOptions::new();
+        opts.flags(::DIFF_INCLUDE_UNTRACKED | ::DIFF_RECURSE_UNTRACKED_DIRS)
+            .pathspec("src/");
+        let diff = t!(repo.diff_tree_to_workdir(None, Some(&mut opts)));
+        let mut paths = diff.deltas().map(|d| {
+            d.new_file().path().unwrap().to_path_buf()
+        }).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, [Path::new("src/lib.rs"),
+                           Path::new("src/nested/mod.rs")]);
+        assert!(diff.deltas().all(|d| d.status() == Delta::Untracked));
+
+        opts.pathspec("top");
+        let diff = t!(repo.diff_tree_to_workdir(None, Some(&mut opts)));
+        assert_eq!(diff.deltas().len(), 3);
+    }
+
+    #[test]
+    fn context_lines() {
+        let (td, repo) = ::test::repo_init();
+        let path = Path::new("foo");
+        t!(t!(File::create(&td.path().join(path)))
+            .write_all(b"a\nb\nc\nd\ne\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(path));
+        t!(index.write());
+        t!(t!(File::create(&td.path().join(path)))
+            .write_all(b"a\nb\nC\nd\ne\n"));
+
+        let hunks = |opts: &mut DiffOptions| {
+            let diff = t!(repo.diff_index_to_workdir(None, Some(opts)));
+            let mut hunks = Vec::new();
+            let mut lines = 0;
+            t!(diff.foreach(&mut |_file, _progress| true,
+                            None,
+                            Some(&mut |_file, hunk| {
+                                hunks.push((hunk.old_start(),
+                                            hunk.old_lines(),
+                                            hunk.new_start(),
+                                            hunk.new_lines()));
+                                true
+                            }),
+                            Some(&mut |_file, _hunk, _line| {
+                                lines += 1;
+                                true
+                            })));
+            (hunks, lines)
+        };
+
+        let (found, lines) = hunks(DiffOptions::new().context_lines(0));
+        assert_eq!(found, [(3, 1, 3, 1)]);
+        assert_eq!(lines, 2);
+
+        let (found, lines) = hunks(DiffOptions::new().context_lines(1)
+                                                   .interhunk_lines(1)
+                                                   .id_abbrev(10));
+        assert_eq!(found, [(2, 3, 2, 3)]);
+        assert_eq!(lines, 4);
+    }
+
+    #[test]
+    fn notify_and_progress() {
+        let (td, repo) = ::test::repo_init();
+        for path in ["a", "b", "c"].iter() {
+            t!(t!(File::create(&td.path().join(path))).write_all(b"text\n"));
+        }
+
+        let mut notified = Vec::new();
+        let mut progressed = Vec::new();
+        {
+            let mut opts = DiffOptions::new();
+            opts.include_untracked(true)
+                .notify(|delta, _pathspec| {
+                    let path = delta.new_file().path().unwrap();
+                    notified.push(path.to_path_buf());
+                    if path == Path::new("b") {
+                        DiffNotifyAction::Skip
+                    } else {
+                        DiffNotifyAction::Include
+                    }
+                })
+                .progress(|_old, new| {
+                    progressed.push(new.unwrap().to_path_buf());
+                    true
+                });
+            let diff = t!(repo.diff_tree_to_workdir(None, Some(&mut opts)));
+            let paths = diff.deltas().map(|d| {
+                d.new_file().path().unwrap().to_path_buf()
+            }).collect::<Vec<_>>();
+            assert_eq!(paths, [Path::new("a"), Path::new("c")]);
+        }
+        notified.sort();
+        assert_eq!(notified, [Path::new("a"), Path::new("b"), Path::new("c")]);
+        assert!(progressed.len() >= 3);
+
+        let mut opts = DiffOptions::new();
+        opts.include_untracked(true)
+            .notify(|_delta, _pathspec| DiffNotifyAction::Abort);
+        let err = repo.diff_tree_to_workdir(None, Some(&mut opts)).err();
+        assert_eq!(err.unwrap().code(), ErrorCode::User);
+    }
 }
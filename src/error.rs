@@ -72,6 +72,7 @@ impl Error {
             raw::GIT_EINVALID => super::ErrorCode::Invalid,
             raw::GIT_EUNCOMMITTED => super::ErrorCode::Uncommitted,
             raw::GIT_EDIRECTORY => super::ErrorCode::Directory,
+            raw::GIT_EMERGECONFLICT => super::ErrorCode::MergeConflict,
             _ => super::ErrorCode::GenericError,
         }
     }
@@ -203,7 +204,6 @@ impl error::Error for Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(f, "[{}/{}] ", self.klass, self.code));
         f.write_str(&self.message)
     }
 }
@@ -221,9 +221,16 @@ impl From<JoinPathsError> for Error {
     }
 }
 
+impl From<str::Utf8Error> for Error {
+    fn from(e: str::Utf8Error) -> Error {
+        Error::from_str(&format!("data was not valid utf-8: {}", e))
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use std::error::Error;
+    use std::str;
     use {ErrorClass, ErrorCode};
 
     #[test]
@@ -234,4 +241,26 @@ mod tests {
         assert_eq!(err.code(), ErrorCode::NotFound);
         assert_eq!(err.class(), ErrorClass::Submodule);
     }
+
+    #[test]
+    fn boxed() {
+        fn lookup(repo: &::Repository) -> Result<(), Box<Error>> {
+            try!(repo.find_submodule("does_not_exist"));
+            Ok(())
+        }
+
+        let (_td, repo) = ::test::repo_init();
+        let err = lookup(&repo).err().unwrap();
+        let git = repo.find_submodule("does_not_exist").err().unwrap();
+        assert_eq!(err.to_string(), git.message());
+        assert_eq!(git.to_string(), git.message());
+
+        let bytes = vec![b'o', b'k', 0xff];
+        let utf8 = str::from_utf8(&bytes).unwrap_err();
+        let err: ::Error = utf8.into();
+        assert_eq!(err.message(),
+                   format!("data was not valid utf-8: {}", utf8));
+        assert!(err.message().contains("index 2"), "{}", err.message());
+        assert_eq!(err.code(), ErrorCode::GenericError);
+    }
 }
@@ -1,4 +1,5 @@
 use std::ffi::{CStr, OsString, CString};
+use std::marker;
 use std::ops::Range;
 use std::path::Path;
 use std::ptr;
@@ -23,6 +24,25 @@ pub struct IndexEntries<'index> {
     index: &'index Index,
 }
 
+/// An iterator over the conflicting entries in an index
+pub struct IndexConflicts<'index> {
+    conflict_iter: *mut raw::git_index_conflict_iterator,
+    _marker: marker::PhantomData<&'index Index>,
+}
+
+/// A structure to represent the information returned when a conflict is
+/// detected in an index entry
+pub struct IndexConflict {
+    /// The ancestor index entry of the two conflicting index entries
+    pub ancestor: Option<IndexEntry>,
+    /// The index entry originating from the user's copy of the repository.
+    /// Its contents conflict with 'their' index entry
+    pub our: Option<IndexEntry>,
+    /// The index entry originating from the external repository.
+    /// Its contents conflict with 'our' index entry
+    pub their: Option<IndexEntry>,
+}
+
 /// A callback function to filter index matches.
 ///
 /// Used by `Index::{add_all,remove_all,update_all}`.  The first argument is the
@@ -88,40 +108,8 @@ impl Index {
     /// given 'source_entry', it will be replaced. Otherwise, the 'source_entry'
     /// will be added.
     pub fn add(&mut self, entry: &IndexEntry) -> Result<(), Error> {
-        let path = try!(CString::new(&entry.path[..]));
-
-        // libgit2 encodes the length of the path in the lower bits of the
-        // `flags` entry, so mask those out and recalculate here to ensure we
-        // don't corrupt anything.
-        let mut flags = entry.flags & !raw::GIT_IDXENTRY_NAMEMASK;
-
-        if entry.path.len() < raw::GIT_IDXENTRY_NAMEMASK as usize {
-            flags |= entry.path.len() as u16;
-        } else {
-            flags |= raw::GIT_IDXENTRY_NAMEMASK;
-        }
-
+        let (_path, raw) = try!(entry_to_raw(entry));
         unsafe {
-            let raw = raw::git_index_entry {
-                dev: entry.dev,
-                ino: entry.ino,
-                mode: entry.mode,
-                uid: entry.uid,
-                gid: entry.gid,
-                file_size: entry.file_size,
-                id: *entry.id.raw(),
-                flags: flags,
-                flags_extended: entry.flags_extended,
-                path: path.as_ptr(),
-                mtime: raw::git_index_time {
-                    seconds: entry.mtime.seconds(),
-                    nanoseconds: entry.mtime.nanoseconds(),
-                },
-                ctime: raw::git_index_time {
-                    seconds: entry.ctime.seconds(),
-                    nanoseconds: entry.ctime.nanoseconds(),
-                },
-            };
             try_call!(raw::git_index_add(self.raw, &raw));
             Ok(())
         }
@@ -243,7 +231,56 @@ impl Index {
         IndexEntries { range: 0..self.len(), index: self }
     }
 
+    /// Get an iterator over the index entries that have conflicts
+    pub fn conflicts(&self) -> Result<IndexConflicts, Error> {
+        ::init();
+        let mut conflict_iter = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_index_conflict_iterator_new(&mut conflict_iter,
+                                                           self.raw));
+            Ok(Binding::from_raw(conflict_iter))
+        }
+    }
+
+    /// Add or update a conflict in the index.
+    ///
+    /// The entries are written as the ancestor, our and their sides of the
+    /// conflict, stages 1, 2 and 3 respectively, regardless of the stage set
+    /// in their flags. Any side may be `None`, such as the ancestor of a file
+    /// added on both sides. Any stage 0 entry for the conflicted paths is
+    /// removed.
+    pub fn add_conflict(&mut self,
+                        ancestor: Option<&IndexEntry>,
+                        our: Option<&IndexEntry>,
+                        their: Option<&IndexEntry>) -> Result<(), Error> {
+        fn convert(entry: Option<&IndexEntry>)
+                   -> Result<Option<(CString, raw::git_index_entry)>, Error> {
+            match entry {
+                Some(entry) => Ok(Some(try!(entry_to_raw(entry)))),
+                None => Ok(None),
+            }
+        }
+        fn as_ptr(entry: &Option<(CString, raw::git_index_entry)>)
+                  -> *const raw::git_index_entry {
+            entry.as_ref().map(|&(_, ref raw)| raw as *const _)
+                 .unwrap_or(ptr::null())
+        }
+        let ancestor = try!(convert(ancestor));
+        let our = try!(convert(our));
+        let their = try!(convert(their));
+        unsafe {
+            try_call!(raw::git_index_conflict_add(self.raw,
+                                                  as_ptr(&ancestor),
+                                                  as_ptr(&our),
+                                                  as_ptr(&their)));
+        }
+        Ok(())
+    }
+
     /// Get one of the entries in the index by its path.
+    ///
+    /// Stage 0 is the normal staged entry, while stages 1, 2 and 3 are the
+    /// ancestor, our and their sides of a conflict respectively.
     pub fn get_path(&self, path: &Path, stage: i32) -> Option<IndexEntry> {
         let path = path.into_c_string().unwrap();
         unsafe {
@@ -253,6 +290,22 @@ impl Index {
         }
     }
 
+    /// Find the position of the first entry whose path begins with `prefix`.
+    ///
+    /// Entries are sorted by path, so all the entries under a directory can
+    /// be listed by calling `get` with increasing positions from the returned
+    /// one for as long as the paths share the prefix. Returns `NotFound` if no
+    /// entry matches.
+    pub fn find_prefix(&self, prefix: &Path) -> Result<usize, Error> {
+        let prefix = try!(prefix.into_c_string());
+        let mut at_pos: size_t = 0;
+        unsafe {
+            try_call!(raw::git_index_find_prefix(&mut at_pos, self.raw,
+                                                 prefix));
+        }
+        Ok(at_pos as usize)
+    }
+
     /// Does this index have conflicts?
     ///
     /// Returns `true` if the index contains conflicts, `false` if it does not.
@@ -467,6 +520,104 @@ impl<'index> Iterator for IndexEntries<'index> {
     }
 }
 
+impl<'index> Binding for IndexConflicts<'index> {
+    type Raw = *mut raw::git_index_conflict_iterator;
+
+    unsafe fn from_raw(raw: *mut raw::git_index_conflict_iterator)
+                       -> IndexConflicts<'index> {
+        IndexConflicts {
+            conflict_iter: raw,
+            _marker: marker::PhantomData,
+        }
+    }
+    fn raw(&self) -> *mut raw::git_index_conflict_iterator {
+        self.conflict_iter
+    }
+}
+
+impl<'index> Iterator for IndexConflicts<'index> {
+    type Item = Result<IndexConflict, Error>;
+    fn next(&mut self) -> Option<Result<IndexConflict, Error>> {
+        let mut ancestor = ptr::null();
+        let mut our = ptr::null();
+        let mut their = ptr::null();
+        unsafe {
+            try_call_iter!(raw::git_index_conflict_next(&mut ancestor,
+                                                        &mut our,
+                                                        &mut their,
+                                                        self.conflict_iter));
+            Some(Ok(IndexConflict {
+                ancestor: opt_entry(ancestor),
+                our: opt_entry(our),
+                their: opt_entry(their),
+            }))
+        }
+    }
+}
+
+impl<'index> Drop for IndexConflicts<'index> {
+    fn drop(&mut self) {
+        unsafe { raw::git_index_conflict_iterator_free(self.conflict_iter) }
+    }
+}
+
+unsafe fn opt_entry(ptr: *const raw::git_index_entry) -> Option<IndexEntry> {
+    if ptr.is_null() {None} else {Some(Binding::from_raw(*ptr))}
+}
+
+impl IndexEntry {
+    /// Return the stage number of this entry.
+    ///
+    /// Entries which are not in conflict are at stage 0, otherwise the stage
+    /// is 1 for the ancestor, 2 for "our" side and 3 for "their" side.
+    pub fn stage(&self) -> i32 {
+        ((self.flags & raw::GIT_IDXENTRY_STAGEMASK) >>
+            raw::GIT_IDXENTRY_STAGESHIFT) as i32
+    }
+}
+
+/// Converts an `IndexEntry` into a raw entry suitable for passing to libgit2.
+///
+/// The raw entry's path points into the returned `CString`, which must be
+/// kept alive for as long as the raw entry is in use.
+pub fn entry_to_raw(entry: &IndexEntry)
+                    -> Result<(CString, raw::git_index_entry), Error> {
+    let path = try!(CString::new(&entry.path[..]));
+
+    // libgit2 encodes the length of the path in the lower bits of the
+    // `flags` entry, so mask those out and recalculate here to ensure we
+    // don't corrupt anything.
+    let mut flags = entry.flags & !raw::GIT_IDXENTRY_NAMEMASK;
+
+    if entry.path.len() < raw::GIT_IDXENTRY_NAMEMASK as usize {
+        flags |= entry.path.len() as u16;
+    } else {
+        flags |= raw::GIT_IDXENTRY_NAMEMASK;
+    }
+
+    let raw = raw::git_index_entry {
+        dev: entry.dev,
+        ino: entry.ino,
+        mode: entry.mode,
+        uid: entry.uid,
+        gid: entry.gid,
+        file_size: entry.file_size,
+        id: unsafe { *entry.id.raw() },
+        flags: flags,
+        flags_extended: entry.flags_extended,
+        path: path.as_ptr(),
+        mtime: raw::git_index_time {
+            seconds: entry.mtime.seconds(),
+            nanoseconds: entry.mtime.nanoseconds(),
+        },
+        ctime: raw::git_index_time {
+            seconds: entry.ctime.seconds(),
+            nanoseconds: entry.ctime.nanoseconds(),
+        },
+    };
+    Ok((path, raw))
+}
+
 impl Binding for IndexEntry {
     type Raw = raw::git_index_entry;
 
@@ -511,6 +662,7 @@ impl Binding for IndexEntry {
 #[cfg(test)]
 mod tests {
     use std::fs::{self, File};
+    use std::io::prelude::*;
     use std::path::Path;
     use tempdir::TempDir;
 
@@ -613,6 +765,62 @@ mod tests {
         index.add(&e).unwrap();
         let e = index.get(0).unwrap();
         assert_eq!(e.path.len(), 6);
+        assert_eq!(e.stage(), 0);
+    }
+
+    #[test]
+    fn entry_stat_data() {
+        let (_td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+
+        let root = repo.path().parent().unwrap();
+        t!(t!(File::create(&root.join("foo"))).write_all(b"foobar"));
+        index.add_path(Path::new("foo")).unwrap();
+
+        let e = index.get_path(Path::new("foo"), 0).unwrap();
+        assert_eq!(e.path, b"foo");
+        assert_eq!(e.file_size, 6);
+        assert_eq!(e.stage(), 0);
+        assert_eq!(e.id, repo.blob(b"foobar").unwrap());
+        assert!(e.mtime.seconds() > 0);
+        assert!(e.ctime.seconds() > 0);
+        assert_eq!(e.mode, 0o100644);
+    }
+
+    #[test]
+    fn add_conflict() {
+        let (_td, repo) = ::test::repo_init();
+        let mut index = t!(Index::new());
+        let side = |contents: &[u8]| {
+            let mut e = entry();
+            e.path = b"foo".to_vec();
+            e.id = t!(repo.blob(contents));
+            e
+        };
+        t!(index.add(&side(b"resolved\n")));
+        assert!(!index.has_conflicts());
+
+        let (base, ours, theirs) = (side(b"base\n"), side(b"ours\n"),
+                                    side(b"theirs\n"));
+        t!(index.add_conflict(Some(&base), Some(&ours), Some(&theirs)));
+        assert!(index.has_conflicts());
+        assert!(index.get_path(Path::new("foo"), 0).is_none());
+
+        let conflicts = t!(index.conflicts()).map(|c| t!(c))
+                                             .collect::<Vec<_>>();
+        assert_eq!(conflicts.len(), 1);
+        let ancestor = conflicts[0].ancestor.as_ref().unwrap();
+        assert_eq!((ancestor.id, ancestor.stage()), (base.id, 1));
+        let our = conflicts[0].our.as_ref().unwrap();
+        assert_eq!((our.id, our.stage()), (ours.id, 2));
+        let their = conflicts[0].their.as_ref().unwrap();
+        assert_eq!((their.id, their.stage()), (theirs.id, 3));
+
+        let mut e = side(b"added\n");
+        e.path = b"bar".to_vec();
+        t!(index.add_conflict(None, Some(&e), Some(&e)));
+        assert!(index.get_path(Path::new("bar"), 1).is_none());
+        assert_eq!(index.get_path(Path::new("bar"), 2).unwrap().id, e.id);
     }
 
     fn entry() -> IndexEntry {
@@ -631,4 +839,66 @@ mod tests {
             path: Vec::new(),
         }
     }
+
+    #[test]
+    fn get_path_stages() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(File::create(td.path().join("foo"))).write_all(b"staged\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("foo")));
+        assert_eq!(index.len(), 1);
+        assert!(!index.is_empty());
+
+        let entry = index.get_path(Path::new("foo"), 0).unwrap();
+        assert_eq!(entry.id, t!(repo.blob(b"staged\n")));
+        assert_eq!(entry.path, b"foo");
+        assert_eq!(index.get(0).unwrap().id, entry.id);
+        assert!(index.get(1).is_none());
+        assert!(index.get_path(Path::new("foo"), 2).is_none());
+        assert!(index.get_path(Path::new("bar"), 0).is_none());
+
+        let sig = t!(repo.signature());
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let commit_with = |contents: &[u8]| {
+            let blob = t!(repo.blob(contents));
+            let mut builder = t!(repo.treebuilder(None));
+            t!(builder.insert("foo", blob, 0o100644));
+            let tree = t!(repo.find_tree(t!(builder.write())));
+            let id = t!(repo.commit(None, &sig, &sig, "msg", &tree, &[&head]));
+            t!(repo.find_commit(id))
+        };
+        let ours = commit_with(b"ours\n");
+        let theirs = commit_with(b"theirs\n");
+        let merged = t!(repo.merge_commits(&ours, &theirs, None));
+        assert!(merged.get_path(Path::new("foo"), 0).is_none());
+        assert!(merged.get_path(Path::new("foo"), 1).is_none());
+        let our = merged.get_path(Path::new("foo"), 2).unwrap();
+        assert_eq!(our.id, t!(repo.blob(b"ours\n")));
+        let their = merged.get_path(Path::new("foo"), 3).unwrap();
+        assert_eq!(their.id, t!(repo.blob(b"theirs\n")));
+    }
+
+    #[test]
+    fn find_prefix() {
+        let (td, repo) = ::test::repo_init();
+        t!(fs::create_dir(td.path().join("src")));
+        t!(fs::create_dir(td.path().join("tests")));
+        let mut index = t!(repo.index());
+        for path in &["tests/c.rs", "src/b.rs", "src/a.rs", "README"] {
+            t!(File::create(td.path().join(path)));
+            t!(index.add_path(Path::new(path)));
+        }
+
+        let pos = t!(index.find_prefix(Path::new("src/")));
+        assert_eq!(index.get(pos).unwrap().path, b"src/a.rs");
+        let names = (pos..index.len()).map(|i| index.get(i).unwrap().path)
+                                      .take_while(|p| p.starts_with(b"src/"))
+                                      .collect::<Vec<_>>();
+        assert_eq!(names, vec![b"src/a.rs".to_vec(), b"src/b.rs".to_vec()]);
+
+        let pos = t!(index.find_prefix(Path::new("tests/")));
+        assert_eq!(index.get(pos).unwrap().path, b"tests/c.rs");
+        let err = index.find_prefix(Path::new("docs/")).err().unwrap();
+        assert_eq!(err.code(), ::ErrorCode::NotFound);
+    }
 }
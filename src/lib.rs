@@ -79,20 +79,24 @@ use std::fmt;
 use std::str;
 use std::sync::{Once, ONCE_INIT};
 
+pub use attr::{AttrValue, AttrForeachCb};
 pub use blame::{Blame, BlameHunk, BlameIter, BlameOptions};
 pub use blob::{Blob, BlobWriter};
 pub use branch::{Branch, Branches};
 pub use buf::Buf;
-pub use commit::{Commit, Parents};
+pub use commit::{Commit, Parents, Ancestors};
 pub use config::{Config, ConfigEntry, ConfigEntries};
 pub use cred::{Cred, CredentialHelper};
 pub use describe::{Describe, DescribeFormatOptions, DescribeOptions};
 pub use diff::{Diff, DiffDelta, DiffFile, DiffOptions, Deltas};
 pub use diff::{DiffBinary, DiffBinaryFile, DiffBinaryKind};
 pub use diff::{DiffLine, DiffHunk, DiffStats, DiffFindOptions};
+pub use diff::{DiffNotify, DiffProgress};
 pub use error::Error;
 pub use index::{Index, IndexEntry, IndexEntries, IndexMatchedPath};
-pub use merge::{AnnotatedCommit, MergeOptions};
+pub use index::{IndexConflict, IndexConflicts};
+pub use merge::{AnnotatedCommit, MergeOptions, MergeFileInput, MergeFileOptions};
+pub use merge::MergeFileResult;
 pub use message::{message_prettify, DEFAULT_COMMENT_CHAR};
 pub use note::{Note, Notes};
 pub use object::Object;
@@ -102,7 +106,7 @@ pub use pathspec::{Pathspec, PathspecMatchList, PathspecFailedEntries};
 pub use pathspec::{PathspecDiffEntries, PathspecEntries};
 pub use patch::Patch;
 pub use proxy_options::ProxyOptions;
-pub use reference::{Reference, References, ReferenceNames};
+pub use reference::{Reference, References, ReferenceNames, FilteredReferences};
 pub use reflog::{Reflog, ReflogEntry, ReflogIter};
 pub use refspec::Refspec;
 pub use remote::{Remote, RemoteConnection, Refspecs, RemoteHead, FetchOptions, PushOptions};
@@ -110,17 +114,19 @@ pub use remote_callbacks::{RemoteCallbacks, Credentials, TransferProgress};
 pub use remote_callbacks::{TransportMessage, Progress, UpdateTips};
 pub use repo::{Repository, RepositoryInitOptions};
 pub use revspec::Revspec;
-pub use revwalk::Revwalk;
+pub use revwalk::{Revwalk, HideCb};
 pub use signature::Signature;
 pub use status::{StatusOptions, Statuses, StatusIter, StatusEntry, StatusShow};
 pub use stash::{StashApplyOptions, StashCb, StashApplyProgressCb};
 pub use submodule::{Submodule, SubmoduleUpdateOptions};
 pub use tag::Tag;
+pub use transaction::Transaction;
 pub use time::{Time, IndexTime};
-pub use tree::{Tree, TreeEntry, TreeIter};
+pub use tree::{Tree, TreeEntry, TreeIter, TreeStats};
 pub use treebuilder::TreeBuilder;
-pub use odb::{Odb, OdbReader, OdbWriter};
+pub use odb::{Odb, OdbReader, OdbWriter, OdbPackwriter};
 pub use util::IntoCString;
+pub use worktree::Worktree;
 
 /// An enumeration of possible errors that can happen when working with a git
 /// repository.
@@ -170,6 +176,8 @@ pub enum ErrorCode {
     Uncommitted,
     /// Operation was not valid for a directory,
     Directory,
+    /// A merge conflict exists and cannot continue
+    MergeConflict,
 }
 
 /// An enumeration of possible categories of things that can have
@@ -304,6 +312,25 @@ pub enum ReferenceType {
     Symbolic,
 }
 
+/// Valid modes for index and tree entries.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum FileMode {
+    /// Unreadable
+    Unreadable,
+    /// Tree
+    Tree,
+    /// Blob
+    Blob,
+    /// Group writable blob. Obsolete mode kept for compatibility reasons
+    BlobGroupWritable,
+    /// Blob executable
+    BlobExecutable,
+    /// Link
+    Link,
+    /// Git commit
+    Commit,
+}
+
 /// An enumeration for the possible types of branches
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum BranchType {
@@ -538,8 +565,10 @@ pub mod build;
 pub mod cert;
 pub mod string_array;
 pub mod oid_array;
+pub mod opts;
 pub mod transport;
 
+mod attr;
 mod blame;
 mod blob;
 mod branch;
@@ -575,8 +604,10 @@ mod submodule;
 mod stash;
 mod tag;
 mod time;
+mod transaction;
 mod tree;
 mod treebuilder;
+mod worktree;
 
 fn init() {
     static INIT: Once = ONCE_INIT;
@@ -795,6 +826,57 @@ impl fmt::Display for ReferenceType {
     }
 }
 
+impl FileMode {
+    /// Convert a raw octal file mode, as returned by `TreeEntry::filemode`,
+    /// to a `FileMode`.
+    ///
+    /// Returns `None` if the mode is not one git knows about.
+    pub fn try_from(mode: i32) -> Option<FileMode> {
+        match mode as raw::git_filemode_t {
+            raw::GIT_FILEMODE_UNREADABLE => Some(FileMode::Unreadable),
+            raw::GIT_FILEMODE_TREE => Some(FileMode::Tree),
+            raw::GIT_FILEMODE_BLOB => Some(FileMode::Blob),
+            raw::GIT_FILEMODE_BLOB_GROUP_WRITABLE => {
+                Some(FileMode::BlobGroupWritable)
+            }
+            raw::GIT_FILEMODE_BLOB_EXECUTABLE => Some(FileMode::BlobExecutable),
+            raw::GIT_FILEMODE_LINK => Some(FileMode::Link),
+            raw::GIT_FILEMODE_COMMIT => Some(FileMode::Commit),
+            _ => None,
+        }
+    }
+
+    /// Get the kind of object an entry with this mode points to.
+    ///
+    /// Returns `None` for `FileMode::Unreadable`.
+    pub fn kind(&self) -> Option<ObjectType> {
+        match *self {
+            FileMode::Unreadable => None,
+            FileMode::Tree => Some(ObjectType::Tree),
+            FileMode::Blob |
+            FileMode::BlobGroupWritable |
+            FileMode::BlobExecutable |
+            FileMode::Link => Some(ObjectType::Blob),
+            FileMode::Commit => Some(ObjectType::Commit),
+        }
+    }
+}
+
+impl From<FileMode> for i32 {
+    fn from(mode: FileMode) -> i32 {
+        let raw = match mode {
+            FileMode::Unreadable => raw::GIT_FILEMODE_UNREADABLE,
+            FileMode::Tree => raw::GIT_FILEMODE_TREE,
+            FileMode::Blob => raw::GIT_FILEMODE_BLOB,
+            FileMode::BlobGroupWritable => raw::GIT_FILEMODE_BLOB_GROUP_WRITABLE,
+            FileMode::BlobExecutable => raw::GIT_FILEMODE_BLOB_EXECUTABLE,
+            FileMode::Link => raw::GIT_FILEMODE_LINK,
+            FileMode::Commit => raw::GIT_FILEMODE_COMMIT,
+        };
+        raw as i32
+    }
+}
+
 impl ConfigLevel {
     /// Converts a raw configuration level to a ConfigLevel
     pub fn from_raw(raw: raw::git_config_level_t) -> ConfigLevel {
@@ -809,6 +891,19 @@ impl ConfigLevel {
             n => panic!("unknown config level: {}", n),
         }
     }
+
+    /// Converts this configuration level to its raw representation
+    pub fn raw(&self) -> raw::git_config_level_t {
+        match *self {
+            ConfigLevel::ProgramData => raw::GIT_CONFIG_LEVEL_PROGRAMDATA,
+            ConfigLevel::System => raw::GIT_CONFIG_LEVEL_SYSTEM,
+            ConfigLevel::XDG => raw::GIT_CONFIG_LEVEL_XDG,
+            ConfigLevel::Global => raw::GIT_CONFIG_LEVEL_GLOBAL,
+            ConfigLevel::Local => raw::GIT_CONFIG_LEVEL_LOCAL,
+            ConfigLevel::App => raw::GIT_CONFIG_LEVEL_APP,
+            ConfigLevel::Highest => raw::GIT_CONFIG_HIGHEST_LEVEL,
+        }
+    }
 }
 
 bitflags! {
@@ -896,6 +991,17 @@ pub enum Delta {
     Conflicted,
 }
 
+/// What to do with a delta reported to a `DiffOptions::notify` callback.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffNotifyAction {
+    /// Add the delta to the diff
+    Include,
+    /// Leave the delta out of the diff
+    Skip,
+    /// Stop generating the diff
+    Abort,
+}
+
 bitflags! {
     /// Return codes for submodule status.
     ///
@@ -1103,7 +1209,7 @@ pub enum FetchPrune {
 }
 
 #[allow(missing_docs)]
-#[derive(Debug)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum StashApplyProgress {
     /// None
     None,
@@ -1151,9 +1257,62 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Flags for the `flags` field of a `DiffDelta` or `DiffFile`.
+    pub struct DiffFlags: u32 {
+        /// File(s) treated as binary data.
+        const DIFF_FLAG_BINARY = raw::GIT_DIFF_FLAG_BINARY as u32;
+        /// File(s) treated as text data.
+        const DIFF_FLAG_NOT_BINARY = raw::GIT_DIFF_FLAG_NOT_BINARY as u32;
+        /// `id` value is known correct.
+        const DIFF_FLAG_VALID_ID = raw::GIT_DIFF_FLAG_VALID_ID as u32;
+        /// File exists at this side of the delta.
+        const DIFF_FLAG_EXISTS = raw::GIT_DIFF_FLAG_EXISTS as u32;
+    }
+}
+
+bitflags! {
+    /// Flags which can be set on `DiffOptions` all at once through
+    /// `DiffOptions::flags`.
+    pub struct DiffOptionFlags: u32 {
+        /// Reverse the sides of the diff.
+        const DIFF_REVERSE = raw::GIT_DIFF_REVERSE;
+        /// Include ignored files in the diff.
+        const DIFF_INCLUDE_IGNORED = raw::GIT_DIFF_INCLUDE_IGNORED;
+        /// Include untracked files in the diff.
+        const DIFF_INCLUDE_UNTRACKED = raw::GIT_DIFF_INCLUDE_UNTRACKED;
+        /// Recurse into untracked directories, listing each file inside.
+        const DIFF_RECURSE_UNTRACKED_DIRS =
+            raw::GIT_DIFF_RECURSE_UNTRACKED_DIRS;
+        /// Ignore all whitespace.
+        const DIFF_IGNORE_WHITESPACE = raw::GIT_DIFF_IGNORE_WHITESPACE;
+        /// Ignore changes in the amount of whitespace.
+        const DIFF_IGNORE_WHITESPACE_CHANGE =
+            raw::GIT_DIFF_IGNORE_WHITESPACE_CHANGE;
+        /// Include the binary contents of changed files in patches.
+        const DIFF_SHOW_BINARY = raw::GIT_DIFF_SHOW_BINARY;
+        /// Use the "patience diff" algorithm.
+        const DIFF_PATIENCE = raw::GIT_DIFF_PATIENCE;
+    }
+}
+
+bitflags! {
+    /// Flags controlling where attributes are looked up.
+    pub struct AttrCheckFlags: u32 {
+        /// Check the working directory, then the index.
+        const ATTR_CHECK_FILE_THEN_INDEX = raw::GIT_ATTR_CHECK_FILE_THEN_INDEX;
+        /// Check the index, then the working directory.
+        const ATTR_CHECK_INDEX_THEN_FILE = raw::GIT_ATTR_CHECK_INDEX_THEN_FILE;
+        /// Check the index only.
+        const ATTR_CHECK_INDEX_ONLY = raw::GIT_ATTR_CHECK_INDEX_ONLY;
+        /// Do not use the system gitattributes file.
+        const ATTR_CHECK_NO_SYSTEM = raw::GIT_ATTR_CHECK_NO_SYSTEM;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ObjectType;
+    use super::{FileMode, ObjectType};
 
     #[test]
     fn convert() {
@@ -1162,4 +1321,20 @@ mod tests {
         assert!(ObjectType::Blob.is_loose());
     }
 
+    #[test]
+    fn file_mode() {
+        assert_eq!(i32::from(FileMode::Blob), 0o100644);
+        assert_eq!(i32::from(FileMode::BlobExecutable), 0o100755);
+        assert_eq!(i32::from(FileMode::Link), 0o120000);
+        assert_eq!(i32::from(FileMode::Tree), 0o040000);
+        assert_eq!(i32::from(FileMode::Commit), 0o160000);
+        assert_eq!(FileMode::try_from(0o100755), Some(FileMode::BlobExecutable));
+        assert_eq!(FileMode::try_from(0o100664),
+                   Some(FileMode::BlobGroupWritable));
+        assert_eq!(FileMode::try_from(0o777), None);
+        assert_eq!(FileMode::Link.kind(), Some(ObjectType::Blob));
+        assert_eq!(FileMode::Commit.kind(), Some(ObjectType::Commit));
+        assert_eq!(FileMode::Unreadable.kind(), None);
+    }
+
 }
@@ -1,8 +1,11 @@
+use std::ffi::CString;
 use std::marker;
 use std::mem;
-use libc::c_uint;
+use std::slice;
+use std::str;
+use libc::{c_uint, c_ushort};
 
-use {raw, Oid, Commit, FileFavor};
+use {raw, Oid, Commit, FileFavor, IntoCString};
 use util::Binding;
 use call::Convert;
 
@@ -21,6 +24,27 @@ pub struct MergeOptions {
     raw: raw::git_merge_options,
 }
 
+/// One side of a three-way file merge performed by
+/// `Repository::merge_file`.
+pub struct MergeFileInput<'a> {
+    raw: raw::git_merge_file_input,
+    path: Option<CString>,
+    _marker: marker::PhantomData<&'a [u8]>,
+}
+
+/// Options to specify when merging the contents of individual files.
+pub struct MergeFileOptions {
+    raw: raw::git_merge_file_options,
+    ancestor_label: Option<CString>,
+    our_label: Option<CString>,
+    their_label: Option<CString>,
+}
+
+/// The result of a three-way file merge.
+pub struct MergeFileResult {
+    raw: raw::git_merge_file_result,
+}
+
 impl<'repo> AnnotatedCommit<'repo> {
     /// Gets the commit ID that the given git_annotated_commit refers to
     pub fn id(&self) -> Oid {
@@ -48,10 +72,33 @@ impl MergeOptions {
 
     /// Detect file renames
     pub fn find_renames(&mut self, find: bool) -> &mut MergeOptions {
-        if find {
-            self.raw.flags |= raw::GIT_MERGE_FIND_RENAMES;
+        self.merge_flag(raw::GIT_MERGE_FIND_RENAMES, find)
+    }
+
+    /// If a conflict occurs, exit immediately instead of attempting to
+    /// continue resolving conflicts
+    pub fn fail_on_conflict(&mut self, fail: bool) -> &mut MergeOptions {
+        self.merge_flag(raw::GIT_MERGE_FAIL_ON_CONFLICT, fail)
+    }
+
+    /// Do not write the REUC extension on the generated index
+    pub fn skip_reuc(&mut self, skip: bool) -> &mut MergeOptions {
+        self.merge_flag(raw::GIT_MERGE_SKIP_REUC, skip)
+    }
+
+    /// If the commits being merged have multiple merge bases, do not build a
+    /// recursive merge base (by merging the multiple merge bases), instead
+    /// simply use the first base.
+    pub fn no_recursive(&mut self, disable: bool) -> &mut MergeOptions {
+        self.merge_flag(raw::GIT_MERGE_NO_RECURSIVE, disable)
+    }
+
+    fn merge_flag(&mut self, opt: raw::git_merge_flag_t, val: bool)
+                  -> &mut MergeOptions {
+        if val {
+            self.raw.flags |= opt;
         } else {
-            self.raw.flags &= !raw::GIT_MERGE_FIND_RENAMES;
+            self.raw.flags &= !opt;
         }
         self
     }
@@ -141,6 +188,209 @@ impl MergeOptions {
     }
 }
 
+impl<'a> Default for MergeFileInput<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MergeFileInput<'a> {
+    /// Creates a new empty input, with no contents, path or mode.
+    pub fn new() -> MergeFileInput<'a> {
+        let mut input = MergeFileInput {
+            raw: unsafe { mem::zeroed() },
+            path: None,
+            _marker: marker::PhantomData,
+        };
+        assert_eq!(unsafe {
+            raw::git_merge_file_init_input(&mut input.raw,
+                                           raw::GIT_MERGE_FILE_INPUT_VERSION)
+        }, 0);
+        input
+    }
+
+    /// Set the path of the file, used to determine the path of the result.
+    pub fn path<T: IntoCString>(&mut self, path: T) -> &mut MergeFileInput<'a> {
+        let path = path.into_c_string().unwrap();
+        self.raw.path = path.as_ptr();
+        self.path = Some(path);
+        self
+    }
+
+    /// Set the file mode, used to determine the mode of the result.
+    pub fn mode(&mut self, mode: u32) -> &mut MergeFileInput<'a> {
+        self.raw.mode = mode as c_uint;
+        self
+    }
+
+    /// Set the contents of the file.
+    pub fn content(&mut self, content: &'a [u8]) -> &mut MergeFileInput<'a> {
+        self.raw.ptr = content.as_ptr() as *const _;
+        self.raw.size = content.len();
+        self
+    }
+
+    /// Acquire a pointer to the underlying raw input.
+    pub unsafe fn raw(&self) -> *const raw::git_merge_file_input {
+        &self.raw as *const _
+    }
+}
+
+impl Default for MergeFileOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MergeFileOptions {
+    /// Creates a default set of file merge options.
+    pub fn new() -> MergeFileOptions {
+        let mut opts = MergeFileOptions {
+            raw: unsafe { mem::zeroed() },
+            ancestor_label: None,
+            our_label: None,
+            their_label: None,
+        };
+        assert_eq!(unsafe {
+            raw::git_merge_file_init_options(&mut opts.raw,
+                                             raw::GIT_MERGE_FILE_OPTIONS_VERSION)
+        }, 0);
+        opts
+    }
+
+    /// Label for the ancestor file side of the conflict which will be
+    /// prepended to labels in diff3-format merge files.
+    pub fn ancestor_label<T: IntoCString>(&mut self, t: T)
+                                          -> &mut MergeFileOptions {
+        let label = t.into_c_string().unwrap();
+        self.raw.ancestor_label = label.as_ptr();
+        self.ancestor_label = Some(label);
+        self
+    }
+
+    /// Label for our file side of the conflict which will be prepended to
+    /// labels in merge files.
+    pub fn our_label<T: IntoCString>(&mut self, t: T) -> &mut MergeFileOptions {
+        let label = t.into_c_string().unwrap();
+        self.raw.our_label = label.as_ptr();
+        self.our_label = Some(label);
+        self
+    }
+
+    /// Label for their file side of the conflict which will be prepended to
+    /// labels in merge files.
+    pub fn their_label<T: IntoCString>(&mut self, t: T) -> &mut MergeFileOptions {
+        let label = t.into_c_string().unwrap();
+        self.raw.their_label = label.as_ptr();
+        self.their_label = Some(label);
+        self
+    }
+
+    /// Specify a side to favor for resolving conflicts
+    pub fn favor(&mut self, favor: FileFavor) -> &mut MergeFileOptions {
+        self.raw.favor = favor.convert();
+        self
+    }
+
+    fn flag(&mut self, opt: raw::git_merge_file_flag_t, val: bool)
+            -> &mut MergeFileOptions {
+        if val {
+            self.raw.flags |= opt;
+        } else {
+            self.raw.flags &= !opt;
+        }
+        self
+    }
+
+    /// Create standard conflicted merge files
+    pub fn style_standard(&mut self, standard: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_STYLE_MERGE, standard)
+    }
+
+    /// Create diff3-style file
+    pub fn style_diff3(&mut self, diff3: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_STYLE_DIFF3, diff3)
+    }
+
+    /// Ignore all whitespace
+    pub fn ignore_whitespace(&mut self, ignore: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_IGNORE_WHITESPACE, ignore)
+    }
+
+    /// Use the "patience diff" algorithm
+    pub fn patience(&mut self, patience: bool) -> &mut MergeFileOptions {
+        self.flag(raw::GIT_MERGE_FILE_DIFF_PATIENCE, patience)
+    }
+
+    /// Set the size of conflict markers (default 7)
+    pub fn marker_size(&mut self, size: u16) -> &mut MergeFileOptions {
+        self.raw.marker_size = size as c_ushort;
+        self
+    }
+
+    /// Acquire a pointer to the underlying raw options.
+    pub unsafe fn raw(&self) -> *const raw::git_merge_file_options {
+        &self.raw as *const _
+    }
+}
+
+impl MergeFileResult {
+    /// True if the output was automerged, false if the output contains
+    /// conflict markers.
+    pub fn automergeable(&self) -> bool {
+        self.raw.automergeable != 0
+    }
+
+    /// The path that the resultant merge file should use, or `None` if a
+    /// filename conflict would occur or the path is not valid utf-8.
+    pub fn path(&self) -> Option<&str> {
+        self.path_bytes().and_then(|b| str::from_utf8(b).ok())
+    }
+
+    /// Gets the path as a byte slice, or `None` if a filename conflict would
+    /// occur.
+    pub fn path_bytes(&self) -> Option<&[u8]> {
+        unsafe { ::opt_bytes(self, self.raw.path) }
+    }
+
+    /// The mode that the resultant merge file should use.
+    pub fn mode(&self) -> u32 {
+        self.raw.mode as u32
+    }
+
+    /// The contents of the merge.
+    pub fn content(&self) -> &[u8] {
+        if self.raw.ptr.is_null() {
+            return &[]
+        }
+        unsafe {
+            slice::from_raw_parts(self.raw.ptr as *const u8, self.raw.len)
+        }
+    }
+}
+
+impl Binding for MergeFileResult {
+    type Raw = raw::git_merge_file_result;
+    unsafe fn from_raw(raw: raw::git_merge_file_result) -> MergeFileResult {
+        MergeFileResult { raw: raw }
+    }
+    fn raw(&self) -> raw::git_merge_file_result {
+        raw::git_merge_file_result {
+            automergeable: self.raw.automergeable,
+            path: self.raw.path,
+            mode: self.raw.mode,
+            ptr: self.raw.ptr,
+            len: self.raw.len,
+        }
+    }
+}
+
+impl Drop for MergeFileResult {
+    fn drop(&mut self) {
+        unsafe { raw::git_merge_file_result_free(&mut self.raw) }
+    }
+}
+
 impl<'repo> Binding for AnnotatedCommit<'repo> {
     type Raw = *mut raw::git_annotated_commit;
     unsafe fn from_raw(raw: *mut raw::git_annotated_commit)
@@ -127,4 +127,25 @@ mod tests {
         assert_eq!(sig.name(), note_obj.committer().name());
         assert!(sig.when() == note_obj.committer().when());
     }
+
+    #[test]
+    fn notes_refs() {
+        let (_td, repo) = ::test::repo_init();
+        assert!(repo.notes_refs().unwrap().is_empty());
+
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        repo.note(&sig, &sig, None, head, "foo", false).unwrap();
+        repo.note(&sig, &sig, Some("refs/notes/review"), head, "bar", false)
+            .unwrap();
+        let mut refs = repo.notes_refs().unwrap();
+        refs.sort();
+        assert_eq!(refs, ["refs/notes/commits", "refs/notes/review"]);
+
+        repo.config().unwrap().set_str("core.notesRef", "refs/notes/review")
+            .unwrap();
+        assert_eq!(repo.note_default_ref().unwrap(), "refs/notes/review");
+        let note = repo.find_note(None, head).unwrap();
+        assert_eq!(note.message(), Some("bar"));
+    }
 }
@@ -1,4 +1,5 @@
 use std::marker;
+use std::mem;
 use std::io;
 use std::ptr;
 use libc::{c_char, c_int, c_void};
@@ -54,6 +55,24 @@ impl<'repo> Odb<'repo> {
         }
     }
 
+    /// Create a stream for writing a pack file to the object database.
+    ///
+    /// The returned writer accepts the raw bytes of a pack file, such as one
+    /// received over a custom transport, which are indexed and made available
+    /// once the writer is committed.
+    pub fn packwriter(&self) -> Result<OdbPackwriter, Error> {
+        let mut out = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_odb_write_pack(&mut out, self.raw, None,
+                                              ptr::null_mut()));
+            Ok(OdbPackwriter {
+                raw: out,
+                progress: mem::zeroed(),
+                _marker: marker::PhantomData,
+            })
+        }
+    }
+
     /// Iterate over all objects in the object database
     pub fn foreach<C>(&self, mut callback: C) -> Result<(), Error>
         where C: FnMut(&Oid) -> bool
@@ -66,6 +85,23 @@ impl<'repo> Odb<'repo> {
             Ok(())
         }
     }
+
+    /// Check whether an object exists in the object database.
+    pub fn exists(&self, oid: Oid) -> bool {
+        unsafe { raw::git_odb_exists(self.raw, oid.raw()) == 1 }
+    }
+
+    /// Refresh the object database to pick up objects added to it on disk by
+    /// something other than this handle, such as another process dropping a
+    /// new pack into `objects/pack`.
+    ///
+    /// Lookups of missing objects already refresh the database on their own,
+    /// so this is only needed to make new objects show up in iteration or to
+    /// avoid paying for that refresh on a later miss.
+    pub fn refresh(&self) -> Result<(), Error> {
+        unsafe { try_call!(raw::git_odb_refresh(self.raw)); }
+        Ok(())
+    }
 }
 
 /// A structure to represent a git ODB rstream
@@ -163,6 +199,54 @@ impl<'repo> io::Write for OdbWriter<'repo> {
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 
+/// A structure to represent a pack file being written to the object database.
+pub struct OdbPackwriter<'repo> {
+    raw: *mut raw::git_odb_writepack,
+    progress: raw::git_transfer_progress,
+    _marker: marker::PhantomData<Object<'repo>>,
+}
+
+impl<'repo> OdbPackwriter<'repo> {
+    /// Finish writing the pack file, indexing it and making its objects
+    /// available in the object database.
+    ///
+    /// If the data written was not a complete pack file an error is returned
+    /// and none of the objects are added.
+    pub fn commit(mut self) -> Result<(), Error> {
+        unsafe {
+            let res = ((*self.raw).commit)(self.raw, &mut self.progress);
+            try!(::call::try(res));
+        }
+        Ok(())
+    }
+}
+
+impl<'repo> Drop for OdbPackwriter<'repo> {
+    fn drop(&mut self) {
+        unsafe { ((*self.raw).free)(self.raw) }
+    }
+}
+
+impl<'repo> io::Write for OdbPackwriter<'repo> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        unsafe {
+            let ptr = buf.as_ptr() as *const c_void;
+            let len = buf.len();
+            let res = ((*self.raw).append)(self.raw, ptr, len,
+                                           &mut self.progress);
+            if res < 0 {
+                let err = Error::last_error(res).unwrap_or_else(|| {
+                    Error::from_str("failed to write to pack stream")
+                });
+                Err(io::Error::new(io::ErrorKind::Other, err))
+            } else {
+                Ok(buf.len())
+            }
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
 pub type ForeachCb<'a> = FnMut(&Oid) -> bool + 'a;
 
 struct ForeachCbData<'a> {
@@ -188,7 +272,7 @@ extern fn foreach_cb(id: *const raw::git_oid,
 mod tests {
     use std::io::prelude::*;
     use tempdir::TempDir;
-    use {Repository, ObjectType};
+    use {Buf, Repository, ObjectType};
 
     #[test]
     #[ignore]
@@ -223,4 +307,48 @@ mod tests {
         let blob = repo.find_blob(id).unwrap();
         assert_eq!(blob.content(), dat);
     }
+
+    #[test]
+    fn packwriter() {
+        let (_td, repo_source) = ::test::repo_init();
+        let td = TempDir::new("test").unwrap();
+        let repo_target = Repository::init(td.path()).unwrap();
+        let head = repo_source.head().unwrap().target().unwrap();
+        let mut buf = Buf::new();
+        {
+            let mut builder = repo_source.packbuilder().unwrap();
+            builder.insert_commit(head).unwrap();
+            builder.write_buf(&mut buf).unwrap();
+        }
+
+        let db = repo_target.odb().unwrap();
+        let mut truncated = db.packwriter().unwrap();
+        truncated.write_all(&buf[..buf.len() / 2]).unwrap();
+        assert!(truncated.commit().is_err());
+        assert!(repo_target.find_commit(head).is_err());
+
+        let mut packwriter = db.packwriter().unwrap();
+        packwriter.write_all(&buf).unwrap();
+        packwriter.commit().unwrap();
+        let commit = repo_target.find_commit(head).unwrap();
+        assert_eq!(commit.message(), Some("initial"));
+        repo_target.find_tree(commit.tree_id()).unwrap();
+    }
+
+    #[test]
+    fn refresh() {
+        let (td, repo) = ::test::repo_init();
+        let db = repo.odb().unwrap();
+        let id = {
+            let other = Repository::open(td.path()).unwrap();
+            other.blob(b"written elsewhere").unwrap()
+        };
+        db.refresh().unwrap();
+        assert!(db.exists(id));
+        let mut found = false;
+        db.foreach(|oid| { found = *oid == id; !found }).unwrap();
+        assert!(found);
+        let blob = repo.find_blob(id).unwrap();
+        assert_eq!(blob.content(), b"written elsewhere");
+    }
 }
@@ -1,10 +1,11 @@
 use std::fmt;
 use std::cmp::Ordering;
 use std::hash::{Hasher, Hash};
+use std::path::Path;
 use std::str;
 use libc;
 
-use {raw, Error};
+use {raw, Error, IntoCString, ObjectType};
 use util::Binding;
 
 /// Unique identity of any object (commit, tree, blob, tag).
@@ -16,8 +17,10 @@ pub struct Oid {
 impl Oid {
     /// Parse a hex-formatted object id into an Oid structure.
     ///
-    /// If the string is not a valid 40-character hex string, an error is
-    /// returned.
+    /// The string may be abbreviated, in which case the remaining digits are
+    /// filled with zeros; this is useful for prefix lookups such as
+    /// `Repository::find_object_by_prefix`. If the string contains non-hex
+    /// characters or is longer than 40 characters, an error is returned.
     pub fn from_str(s: &str) -> Result<Oid, Error> {
         ::init();
         let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
@@ -44,12 +47,51 @@ impl Oid {
         }
     }
 
+    /// Hashes the provided data as an object of the provided type, and returns
+    /// an Oid corresponding to the result. This does not store the object
+    /// inside any object database or repository.
+    pub fn hash_object(kind: ObjectType, bytes: &[u8]) -> Result<Oid, Error> {
+        ::init();
+        let mut out = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        unsafe {
+            try_call!(raw::git_odb_hash(&mut out,
+                                        bytes.as_ptr()
+                                            as *const libc::c_void,
+                                        bytes.len() as libc::size_t,
+                                        kind.raw()));
+        }
+        Ok(Oid { raw: out })
+    }
+
+    /// Hashes the content of the provided file as an object of the provided
+    /// type, and returns an Oid corresponding to the result. This does not
+    /// store the object inside any object database or repository.
+    pub fn hash_file<P: AsRef<Path>>(kind: ObjectType, path: P)
+                                     -> Result<Oid, Error> {
+        ::init();
+        let rpath = try!(path.as_ref().into_c_string());
+        let mut out = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        unsafe {
+            try_call!(raw::git_odb_hashfile(&mut out, rpath, kind.raw()));
+        }
+        Ok(Oid { raw: out })
+    }
+
     /// View this OID as a byte-slice 20 bytes in length.
     pub fn as_bytes(&self) -> &[u8] { &self.raw.id }
 
-    /// Test if this OID is all zeros.
+    /// Creates an all-zero OID.
+    ///
+    /// This is the "null" id which libgit2 uses to represent a missing
+    /// object, such as the old value of a newly created reference in its
+    /// reflog. It does not require libgit2 to be initialized.
+    pub fn zero() -> Oid {
+        Oid { raw: raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] } }
+    }
+
+    /// Test if this OID is all zeros, i.e. equal to `Oid::zero()`.
     pub fn is_zero(&self) -> bool {
-        unsafe { raw::git_oid_iszero(&self.raw) == 1 }
+        self.raw.id.iter().all(|&b| b == 0)
     }
 }
 
@@ -86,8 +128,8 @@ impl str::FromStr for Oid {
 
     /// Parse a hex-formatted object id into an Oid structure.
     ///
-    /// If the string is not a valid 40-character hex string, an error is
-    /// returned.
+    /// This is equivalent to `Oid::from_str`, so abbreviated ids are
+    /// accepted and padded with zeros.
     fn from_str(s: &str) -> Result<Oid, Error> {
         Oid::from_str(s)
     }
@@ -128,7 +170,15 @@ impl AsRef<[u8]> for Oid {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+    use std::collections::hash_map::DefaultHasher;
+    use std::fs::File;
+    use std::hash::{Hash, Hasher};
+    use std::io::prelude::*;
+
+    use tempdir::TempDir;
     use super::Oid;
+    use ObjectType;
 
     #[test]
     fn conversions() {
@@ -137,4 +187,81 @@ mod tests {
         assert!(Oid::from_bytes(b"foo").is_err());
         assert!(Oid::from_bytes(b"00000000000000000000").is_ok());
     }
+
+    #[test]
+    fn zero() {
+        let zero = Oid::zero();
+        assert!(zero.is_zero());
+        assert_eq!(zero.to_string(), "0".repeat(40));
+        assert_eq!(zero.as_bytes(), &[0; 20][..]);
+        assert_eq!(Oid::from_str(&zero.to_string()).unwrap(), zero);
+        let id = Oid::hash_object(ObjectType::Blob, b"").unwrap();
+        assert!(!id.is_zero());
+        assert!(id != zero);
+    }
+
+    #[test]
+    fn eq_hash_and_ord() {
+        let hex = "decbf2be529ab6557d5429922251e5ee36519817";
+        let a = Oid::from_str(hex).unwrap();
+        let b: Oid = hex.parse().unwrap();
+        assert_eq!(a, b);
+        let (mut ha, mut hb) = (DefaultHasher::new(), DefaultHasher::new());
+        a.hash(&mut ha);
+        b.hash(&mut hb);
+        assert_eq!(ha.finish(), hb.finish());
+        let set = [a, b].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(set.len(), 1);
+
+        let mut ids = ["ff00000000000000000000000000000000000000",
+                       "0000000000000000000000000000000000000001",
+                       "00ff000000000000000000000000000000000000",
+                       "0100000000000000000000000000000000000000"]
+            .iter().map(|s| Oid::from_str(s).unwrap()).collect::<Vec<_>>();
+        let mut bytes = ids.iter().map(|id| id.as_bytes().to_vec())
+                           .collect::<Vec<_>>();
+        ids.sort();
+        bytes.sort();
+        assert_eq!(ids.iter().map(|id| id.as_bytes().to_vec())
+                      .collect::<Vec<_>>(), bytes);
+        assert!(ids[0] < ids[1]);
+        assert_eq!(format!("{:?}", ids[0]), ids[0].to_string());
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let hex = "1234567890123456789012345678901234567890";
+        let id = hex.parse::<Oid>().unwrap();
+        assert_eq!(id.to_string(), hex);
+        assert_eq!(id.to_string().parse::<Oid>().unwrap(), id);
+
+        let upper = "DECBF2BE529AB6557D5429922251E5EE36519817";
+        assert_eq!(upper.parse::<Oid>().unwrap().to_string(),
+                   upper.to_lowercase());
+
+        let short = "abc".parse::<Oid>().unwrap();
+        assert_eq!(short.to_string(),
+                   format!("abc{}", "0".repeat(37)));
+
+        assert!("xyz".parse::<Oid>().is_err());
+        assert!(format!("{}0", hex).parse::<Oid>().is_err());
+    }
+
+    #[test]
+    fn hash_object() {
+        let id = Oid::hash_object(ObjectType::Blob, b"hello\n").unwrap();
+        assert_eq!(id.to_string(), "ce013625030ba8dba906f756967f9e9ca394464a");
+        let empty = Oid::hash_object(ObjectType::Blob, b"").unwrap();
+        assert_eq!(empty.to_string(), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    }
+
+    #[test]
+    fn hash_file() {
+        let td = TempDir::new("test").unwrap();
+        let path = td.path().join("hello.txt");
+        File::create(&path).unwrap().write_all(b"hello\n").unwrap();
+        let id = Oid::hash_file(ObjectType::Blob, &path).unwrap();
+        assert_eq!(id, Oid::hash_object(ObjectType::Blob, b"hello\n").unwrap());
+        assert!(Oid::hash_file(ObjectType::Blob, td.path().join("nope")).is_err());
+    }
 }
@@ -0,0 +1,172 @@
+//! Bindings to libgit2's global options, set through `git_libgit2_opts`.
+//!
+//! These options are process-global rather than per-repository. libgit2 does
+//! not synchronize access to most of them, so every function in this module
+//! is `unsafe`: callers must ensure that no other thread is using libgit2
+//! while an option is being read or changed. The usual approach is to
+//! configure libgit2 once at startup, before spawning any threads which open
+//! repositories.
+
+use std::path::{Path, PathBuf};
+use libc::{c_int, size_t, ssize_t};
+
+use {raw, Buf, ConfigLevel, Error, IntoCString, ObjectType};
+use util::{self, Binding};
+
+fn check(ret: c_int) -> Result<(), Error> {
+    try!(::call::try(ret));
+    Ok(())
+}
+
+/// Get the maximum mmap window size.
+pub unsafe fn get_mwindow_size() -> Result<usize, Error> {
+    ::init();
+    let mut size = 0 as size_t;
+    try!(check(raw::git_libgit2_opts(raw::GIT_OPT_GET_MWINDOW_SIZE as c_int,
+                                     &mut size as *mut size_t)));
+    Ok(size as usize)
+}
+
+/// Set the maximum mmap window size.
+pub unsafe fn set_mwindow_size(size: usize) -> Result<(), Error> {
+    ::init();
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_MWINDOW_SIZE as c_int,
+                                size as size_t))
+}
+
+/// Get the maximum memory that will be mapped in total by the library.
+pub unsafe fn get_mwindow_mapped_limit() -> Result<usize, Error> {
+    ::init();
+    let mut limit = 0 as size_t;
+    try!(check(raw::git_libgit2_opts(raw::GIT_OPT_GET_MWINDOW_MAPPED_LIMIT as c_int,
+                                     &mut limit as *mut size_t)));
+    Ok(limit as usize)
+}
+
+/// Set the maximum amount of memory that can be mapped at any time by the
+/// library.
+pub unsafe fn set_mwindow_mapped_limit(limit: usize) -> Result<(), Error> {
+    ::init();
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_MWINDOW_MAPPED_LIMIT as c_int,
+                                limit as size_t))
+}
+
+/// Set the maximum data size for objects of the given type to be eligible
+/// for caching.
+///
+/// Setting the size to 0 disables caching for that object type. Blobs are
+/// not cached by default.
+pub unsafe fn set_cache_object_limit(kind: ObjectType, size: usize)
+                                     -> Result<(), Error> {
+    ::init();
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_CACHE_OBJECT_LIMIT as c_int,
+                                kind.raw() as c_int,
+                                size as size_t))
+}
+
+/// Set the maximum total data size that will be cached in memory across all
+/// repositories before libgit2 starts evicting objects.
+///
+/// The default is 256MB.
+pub unsafe fn set_cache_max_size(size: isize) -> Result<(), Error> {
+    ::init();
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_CACHE_MAX_SIZE as c_int,
+                                size as ssize_t))
+}
+
+/// Enable or disable caching completely.
+///
+/// Disabling caching doesn't free objects which are already cached; they
+/// are released the next time each repository's cache is used.
+pub unsafe fn enable_caching(enabled: bool) -> Result<(), Error> {
+    ::init();
+    check(raw::git_libgit2_opts(raw::GIT_OPT_ENABLE_CACHING as c_int,
+                                enabled as c_int))
+}
+
+/// Get the current number of bytes held in the cache and the maximum that
+/// the cache is allowed to hold, in that order.
+pub unsafe fn cached_memory() -> Result<(usize, usize), Error> {
+    ::init();
+    let mut current = 0 as ssize_t;
+    let mut allowed = 0 as ssize_t;
+    try!(check(raw::git_libgit2_opts(raw::GIT_OPT_GET_CACHED_MEMORY as c_int,
+                                     &mut current as *mut ssize_t,
+                                     &mut allowed as *mut ssize_t)));
+    Ok((current as usize, allowed as usize))
+}
+
+/// Get the search path for a given level of config data.
+///
+/// `level` must be one of `ConfigLevel::System`, `ConfigLevel::Global`,
+/// `ConfigLevel::XDG` or `ConfigLevel::ProgramData`.
+pub unsafe fn get_search_path(level: ConfigLevel) -> Result<PathBuf, Error> {
+    ::init();
+    let buf = Buf::new();
+    try!(check(raw::git_libgit2_opts(raw::GIT_OPT_GET_SEARCH_PATH as c_int,
+                                     level.raw() as c_int,
+                                     buf.raw())));
+    Ok(util::bytes2path(&buf).to_path_buf())
+}
+
+/// Set the search path for a level of config data.
+///
+/// The path may contain several directories separated by the platform's
+/// path list separator. Subsequent lookups of config files at that level,
+/// such as `Config::find_global` and `Config::open_default`, will look in
+/// these directories instead of the default locations. `level` has the same
+/// restrictions as for `get_search_path`.
+pub unsafe fn set_search_path<P>(level: ConfigLevel, path: P)
+                                 -> Result<(), Error>
+    where P: AsRef<Path>
+{
+    ::init();
+    let path = try!(path.as_ref().into_c_string());
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_SEARCH_PATH as c_int,
+                                level.raw() as c_int,
+                                path.as_ptr()))
+}
+
+/// Get the default template path used by `Repository::init`.
+pub unsafe fn get_template_path() -> Result<PathBuf, Error> {
+    ::init();
+    let buf = Buf::new();
+    try!(check(raw::git_libgit2_opts(raw::GIT_OPT_GET_TEMPLATE_PATH as c_int,
+                                     buf.raw())));
+    Ok(util::bytes2path(&buf).to_path_buf())
+}
+
+/// Set the default template path used by `Repository::init`.
+pub unsafe fn set_template_path<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    ::init();
+    let path = try!(path.as_ref().into_c_string());
+    check(raw::git_libgit2_opts(raw::GIT_OPT_SET_TEMPLATE_PATH as c_int,
+                                path.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mwindow() {
+        unsafe {
+            let size = t!(get_mwindow_size());
+            t!(set_mwindow_size(size));
+            assert_eq!(t!(get_mwindow_size()), size);
+
+            let limit = t!(get_mwindow_mapped_limit());
+            t!(set_mwindow_mapped_limit(limit));
+            assert_eq!(t!(get_mwindow_mapped_limit()), limit);
+        }
+    }
+
+    #[test]
+    fn template_path() {
+        unsafe {
+            let old = t!(get_template_path());
+            t!(set_template_path(&old));
+            assert_eq!(t!(get_template_path()), old);
+        }
+    }
+}
@@ -25,6 +25,13 @@ pub struct References<'repo> {
     _marker: marker::PhantomData<Refdb<'repo>>,
 }
 
+/// An iterator over the references in a repository of a single type, either
+/// direct or symbolic.
+pub struct FilteredReferences<'repo> {
+    inner: References<'repo>,
+    kind: ReferenceType,
+}
+
 /// An iterator over the names of references in a repository.
 pub struct ReferenceNames<'repo: 'references, 'references> {
     inner: &'references mut References<'repo>,
@@ -47,8 +54,11 @@ impl<'repo> Reference<'repo> {
     /// will be immediately removed on disk.
     ///
     /// This function will return an error if the reference has changed from the
-    /// time it was looked up.
+    /// time it was looked up, or if it is `HEAD`.
     pub fn delete(&mut self) -> Result<(), Error> {
+        if self.name_bytes() == b"HEAD" {
+            return Err(Error::from_str("cannot delete HEAD"))
+        }
         unsafe { try_call!(raw::git_reference_delete(self.raw)); }
         Ok(())
     }
@@ -287,6 +297,16 @@ impl<'repo> References<'repo> {
     pub fn names<'a>(&'a mut self) -> ReferenceNames<'repo, 'a> {
         ReferenceNames { inner: self }
     }
+
+    /// Consumes a `References` iterator to create an iterator over only the
+    /// references of the given type.
+    ///
+    /// References of other types are skipped without their names being
+    /// allocated. Note that the underlying iterator only covers references
+    /// under `refs/`, so `HEAD` itself is never yielded.
+    pub fn filter_by(self, kind: ReferenceType) -> FilteredReferences<'repo> {
+        FilteredReferences { inner: self, kind: kind }
+    }
 }
 
 impl<'repo> Binding for References<'repo> {
@@ -315,6 +335,22 @@ impl<'repo> Drop for References<'repo> {
     }
 }
 
+impl<'repo> Iterator for FilteredReferences<'repo> {
+    type Item = Result<Reference<'repo>, Error>;
+    fn next(&mut self) -> Option<Result<Reference<'repo>, Error>> {
+        loop {
+            match self.inner.next() {
+                Some(Ok(r)) => {
+                    if r.kind() == Some(self.kind) {
+                        return Some(Ok(r))
+                    }
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
 impl<'repo, 'references> Iterator for ReferenceNames<'repo, 'references> {
     type Item = Result<&'references str, Error>;
     fn next(&mut self) -> Option<Result<&'references str, Error>> {
@@ -398,4 +434,98 @@ mod tests {
         head.delete().unwrap();
 
     }
+
+    #[test]
+    fn predicates() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+        let sig = repo.signature().unwrap();
+
+        let remote = repo.reference("refs/remotes/origin/master", head, false,
+                                    "test").unwrap();
+        assert!(remote.is_remote());
+        assert!(!remote.is_branch());
+        assert!(!remote.is_tag());
+        assert!(!remote.is_note());
+
+        repo.note(&sig, &sig, None, head, "note", false).unwrap();
+        let note = repo.find_reference("refs/notes/commits").unwrap();
+        assert!(note.is_note());
+        assert!(!note.is_branch());
+        assert!(!note.is_remote());
+        assert!(!note.is_tag());
+        assert_eq!(note.kind(), Some(ReferenceType::Oid));
+        assert!(note.target_peel().is_none());
+
+        let branch = repo.find_reference("refs/heads/master").unwrap();
+        assert!(branch.is_branch());
+        let tag = repo.reference("refs/tags/v1", head, false, "test").unwrap();
+        assert!(tag.is_tag());
+        assert!(!tag.is_branch());
+    }
+
+    #[test]
+    fn filter_by_kind() {
+        let (_td, repo) = ::test::repo_init();
+        let refs = t!(repo.references()).filter_by(ReferenceType::Symbolic);
+        assert_eq!(refs.count(), 0);
+
+        let head = t!(repo.head());
+        let head_name = head.name().unwrap();
+        t!(repo.reference_symbolic("refs/heads/alias", head_name, false,
+                                   "alias"));
+
+        let names = t!(repo.references()).filter_by(ReferenceType::Symbolic)
+            .map(|r| t!(r).name().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["refs/heads/alias"]);
+
+        let names = t!(repo.references()).filter_by(ReferenceType::Oid)
+            .map(|r| t!(r).name().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![head_name]);
+    }
+
+    #[test]
+    fn shorthand() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head());
+        assert_eq!(head.name(), Some("refs/heads/master"));
+        assert_eq!(head.shorthand(), Some("master"));
+        assert_eq!(head.shorthand_bytes(), b"master");
+
+        let id = head.target().unwrap();
+        let remote = t!(repo.reference("refs/remotes/origin/main", id, false,
+                                       "remote"));
+        assert_eq!(remote.shorthand(), Some("origin/main"));
+        let tag = t!(repo.reference("refs/tags/v1.0", id, false, "tag"));
+        assert_eq!(tag.shorthand(), Some("v1.0"));
+        let other = t!(repo.reference("refs/other/thing", id, false, "other"));
+        assert_eq!(other.shorthand(), Some("other/thing"));
+    }
+
+    #[test]
+    fn delete_and_remove() {
+        let (_td, repo) = ::test::repo_init();
+        let id = t!(repo.head()).target().unwrap();
+
+        let mut custom = t!(repo.reference("refs/custom/foo", id, false,
+                                           "custom"));
+        t!(custom.delete());
+        assert!(repo.find_reference("refs/custom/foo").is_err());
+        assert!(repo.refname_to_id("refs/custom/foo").is_err());
+
+        t!(repo.reference("refs/custom/foo", id, false, "custom"));
+        t!(repo.reference_remove("refs/custom/foo"));
+        assert!(repo.find_reference("refs/custom/foo").is_err());
+
+        t!(repo.reference_symbolic("refs/custom/dangling",
+                                   "refs/heads/missing", false, "dangling"));
+        t!(repo.reference_remove("refs/custom/dangling"));
+        assert!(repo.find_reference("refs/custom/dangling").is_err());
+
+        assert!(repo.reference_remove("HEAD").is_err());
+        assert!(t!(repo.find_reference("HEAD")).delete().is_err());
+        assert!(repo.head().is_ok());
+    }
 }
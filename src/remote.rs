@@ -361,13 +361,19 @@ impl<'remote> RemoteHead<'remote> {
     }
 
     pub fn name(&self) -> &str {
-        let b = unsafe { ::opt_bytes(self, (*self.raw).name).unwrap() };
-        str::from_utf8(b).unwrap()
+        str::from_utf8(self.name_bytes()).unwrap()
+    }
+
+    pub fn name_bytes(&self) -> &[u8] {
+        unsafe { ::opt_bytes(self, (*self.raw).name).unwrap() }
     }
 
     pub fn symref_target(&self) -> Option<&str> {
-        let b = unsafe { ::opt_bytes(self, (*self.raw).symref_target) };
-        b.map(|b| str::from_utf8(b).unwrap())
+        self.symref_target_bytes().map(|b| str::from_utf8(b).unwrap())
+    }
+
+    pub fn symref_target_bytes(&self) -> Option<&[u8]> {
+        unsafe { ::opt_bytes(self, (*self.raw).symref_target) }
     }
 }
 
@@ -541,7 +547,7 @@ mod tests {
     use std::cell::Cell;
     use tempdir::TempDir;
     use {Repository, Remote, RemoteCallbacks, Direction, FetchOptions};
-    use {AutotagOption, PushOptions};
+    use {AutotagOption, PushOptions, ErrorCode};
 
     #[test]
     fn smoke() {
@@ -629,6 +635,28 @@ mod tests {
         t!(repo.remote_add_fetch("origin", "bar"));
     }
 
+    #[test]
+    fn set_remote_urls() {
+        let (_td, repo) = ::test::repo_init();
+        let origin = repo.remote("origin", "/path/to/origin").unwrap();
+        assert_eq!(origin.url(), Some("/path/to/origin"));
+        assert_eq!(origin.pushurl(), None);
+
+        t!(repo.remote_set_url("origin", "/path/to/fetch"));
+        t!(repo.remote_set_pushurl("origin", Some("/path/to/push")));
+        // in-memory remotes keep their old urls
+        assert_eq!(origin.url(), Some("/path/to/origin"));
+
+        let origin = t!(repo.find_remote("origin"));
+        assert_eq!(origin.name(), Some("origin"));
+        assert_eq!(origin.url(), Some("/path/to/fetch"));
+        assert_eq!(origin.pushurl(), Some("/path/to/push"));
+
+        t!(repo.remote_set_pushurl("origin", None));
+        let origin = t!(repo.find_remote("origin"));
+        assert_eq!(origin.pushurl(), None);
+    }
+
     #[test]
     fn rename_remote() {
         let (_td, repo) = ::test::repo_init();
@@ -637,6 +665,26 @@ mod tests {
         repo.remote_delete("foo").unwrap();
     }
 
+    #[test]
+    fn delete_remote() {
+        let (_td, repo) = ::test::repo_init();
+        repo.remote("upstream", "/path/to/upstream").unwrap();
+        {
+            let remotes = repo.remotes().unwrap();
+            assert_eq!(remotes.len(), 1);
+            assert_eq!(remotes.get(0), Some("upstream"));
+        }
+
+        let problems = repo.remote_rename("upstream", "mirror").unwrap();
+        assert_eq!(problems.len(), 0);
+        assert!(repo.find_remote("upstream").is_err());
+        assert_eq!(repo.find_remote("mirror").unwrap().name(), Some("mirror"));
+
+        repo.remote_delete("mirror").unwrap();
+        assert_eq!(repo.remotes().unwrap().len(), 0);
+        assert!(repo.find_remote("mirror").is_err());
+    }
+
     #[test]
     fn create_remote_anonymous() {
         let td = TempDir::new("test").unwrap();
@@ -683,6 +731,49 @@ mod tests {
         assert!(progress_hit.get());
     }
 
+    #[test]
+    fn transfer_cb_cancel() {
+        let (td, _repo) = ::test::repo_init();
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.transfer_progress(|_progress| false);
+        let mut origin = repo.remote("origin", &url).unwrap();
+        let err = origin.fetch(&[],
+                               Some(FetchOptions::new()
+                                        .remote_callbacks(callbacks)),
+                               None).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::GenericError);
+    }
+
+    #[test]
+    fn sideband_cb() {
+        let (td, _repo) = ::test::repo_init();
+        let td2 = TempDir::new("git").unwrap();
+        let url = ::test::path2url(&td.path());
+
+        let repo = Repository::init(td2.path()).unwrap();
+        let mut text = Vec::new();
+        {
+            let mut callbacks = RemoteCallbacks::new();
+            callbacks.sideband_progress(|data| {
+                text.extend_from_slice(data);
+                true
+            });
+            callbacks.certificate_check(|_cert, _host| {
+                panic!("no certificate for a local transport")
+            });
+            let mut origin = repo.remote("origin", &url).unwrap();
+            origin.fetch(&[],
+                         Some(FetchOptions::new().remote_callbacks(callbacks)),
+                         None).unwrap();
+        }
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("Counting objects"), "{}", text);
+    }
+
     /// This test is meant to assure that the callbacks provided to connect will not cause
     /// segfaults
     #[test]
@@ -133,6 +133,9 @@ impl<'a> RemoteCallbacks<'a> {
     /// If certificate verification fails, then this callback will be invoked to
     /// let the caller make the final decision of whether to allow the
     /// connection to proceed.
+    ///
+    /// Returning `false` from the callback aborts the connection, and the
+    /// operation fails with an error whose code is `ErrorCode::Auth`.
     pub fn certificate_check<F>(&mut self, cb: F) -> &mut RemoteCallbacks<'a>
         where F: FnMut(&Cert, &str) -> bool + 'a
     {
@@ -362,7 +365,11 @@ extern fn certificate_check_cb(cert: *mut raw::git_cert,
                            .unwrap();
         callback(&cert, hostname)
     });
-    if ok == Some(true) {0} else {-1}
+    match ok {
+        Some(true) => 0,
+        Some(false) => raw::GIT_EAUTH as c_int,
+        None => -1,
+    }
 }
 
 extern fn push_update_reference_cb(refname: *const c_char,
@@ -1,8 +1,10 @@
 use std::env;
 use std::ffi::{CStr, CString, OsStr};
+use std::fs::File;
+use std::io::Write;
 use std::iter::IntoIterator;
 use std::mem;
-use std::path::Path;
+use std::path::{Component, Path};
 use std::ptr;
 use std::str;
 use libc::{c_int, c_char, size_t, c_void, c_uint};
@@ -14,10 +16,16 @@ use {AnnotatedCommit, MergeOptions, SubmoduleIgnore, SubmoduleStatus, MergeAnaly
 use {ObjectType, Tag, Note, Notes, StatusOptions, Statuses, Status, Revwalk};
 use {RevparseMode, RepositoryInitMode, Reflog, IntoCString, Describe};
 use {DescribeOptions, TreeBuilder, Diff, DiffOptions, PackBuilder, Odb};
+use {AttrCheckFlags, AttrValue, ErrorCode};
+use {MergeFileInput, MergeFileOptions, MergeFileResult, IndexEntry, Worktree};
+use {Transaction, Delta, DiffFindOptions, ConfigLevel, TreeStats};
+use attr::{self, AttrForeachCbData, attr_foreach_cb};
 use build::{RepoBuilder, CheckoutBuilder};
 use stash::{StashApplyOptions, StashCbData, stash_cb};
 use string_array::StringArray;
 use oid_array::OidArray;
+use branch;
+use index;
 use util::{self, Binding};
 
 /// An owned git repository, representing all state associated with the
@@ -142,6 +150,20 @@ impl Repository {
         Repository::open(util::bytes2path(&*buf))
     }
 
+    /// Open the repository of a linked worktree.
+    ///
+    /// The returned repository operates on the worktree's own HEAD, index
+    /// and working directory, so for example `set_head` on it changes only
+    /// that worktree's HEAD.
+    pub fn open_from_worktree(worktree: &Worktree) -> Result<Repository, Error> {
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_open_from_worktree(&mut ret,
+                                                             worktree.raw()));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
     /// Creates a new repository in the specified folder.
     ///
     /// This by default will create any necessary directories to create the
@@ -151,6 +173,21 @@ impl Repository {
         Repository::init_opts(path, &RepositoryInitOptions::new())
     }
 
+    /// Create a repository which wraps an existing object database.
+    ///
+    /// The returned repository has no working directory, configuration or
+    /// references of its own; objects can be read from and written to the
+    /// object database, and the other parts of a repository can be supplied
+    /// through methods such as `set_config` and `set_index`.
+    pub fn from_odb(odb: Odb) -> Result<Repository, Error> {
+        init();
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_wrap_odb(&mut ret, odb.raw()));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
     /// Creates a new `--bare` repository in the specified folder.
     ///
     /// The folder must exist prior to invoking this function.
@@ -247,6 +284,28 @@ impl Repository {
         }
     }
 
+    /// Check whether a revision string resolves to an object.
+    ///
+    /// Specs which don't resolve or aren't valid revision syntax yield
+    /// `Ok(false)`, while other errors, such as a corrupt object database,
+    /// are returned.
+    pub fn try_rev_exists(&self, spec: &str) -> Result<bool, Error> {
+        match self.revparse_single(spec) {
+            Ok(..) => Ok(true),
+            Err(ref e) if e.code() == ErrorCode::NotFound ||
+                          e.code() == ErrorCode::InvalidSpec => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a revision string resolves to an object.
+    ///
+    /// This is like `try_rev_exists`, except that any error is treated as the
+    /// spec not existing.
+    pub fn rev_exists(&self, spec: &str) -> bool {
+        self.try_rev_exists(spec).unwrap_or(false)
+    }
+
     /// Find a single object and intermediate reference by a revision string.
     ///
     /// See `man gitrevisions`, or
@@ -296,6 +355,23 @@ impl Repository {
         }
     }
 
+    /// Returns the path to the git directory shared by all worktrees of this
+    /// repository.
+    ///
+    /// For a linked worktree this is the `.git` folder of the main
+    /// repository; otherwise it is the same as `path`.
+    pub fn commondir(&self) -> &Path {
+        unsafe {
+            let ptr = raw::git_repository_commondir(self.raw);
+            util::bytes2path(::opt_bytes(self, ptr).unwrap())
+        }
+    }
+
+    /// Tests whether this repository is a linked worktree.
+    pub fn is_worktree(&self) -> bool {
+        unsafe { raw::git_repository_is_worktree(self.raw) == 1 }
+    }
+
     /// Returns the current state of this repository
     pub fn state(&self) -> RepositoryState {
         let state = unsafe { raw::git_repository_state(self.raw) };
@@ -368,6 +444,34 @@ impl Repository {
         unsafe { ::opt_bytes(self, raw::git_repository_get_namespace(self.raw)) }
     }
 
+    /// Set the active namespace for this repository.
+    ///
+    /// Once set, references are read from and written to
+    /// `refs/namespaces/<namespace>/` instead of the top-level `refs/`. The
+    /// namespace is picked up when the reference database is first loaded, so
+    /// this should be called before any references are accessed.
+    pub fn set_namespace(&self, namespace: &str) -> Result<(), Error> {
+        self.set_namespace_bytes(namespace.as_bytes())
+    }
+
+    /// Set the active namespace for this repository as a byte array.
+    pub fn set_namespace_bytes(&self, namespace: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let namespace = try!(CString::new(namespace));
+            try_call!(raw::git_repository_set_namespace(self.raw, namespace));
+            Ok(())
+        }
+    }
+
+    /// Remove the active namespace for this repository.
+    pub fn remove_namespace(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_repository_set_namespace(self.raw,
+                                                        ptr::null()));
+            Ok(())
+        }
+    }
+
     /// List all remotes for a given repository
     pub fn remotes(&self) -> Result<StringArray, Error> {
         let mut arr = raw::git_strarray {
@@ -380,6 +484,29 @@ impl Repository {
         }
     }
 
+    /// List the `(name, url)` pairs of the remotes configured in this
+    /// repository's configuration, parsed from its `remote.<name>.url` keys.
+    ///
+    /// Entries whose name or url are not valid utf-8 are skipped, as are
+    /// malformed keys with an empty remote name.
+    pub fn remotes_config(&self) -> Result<Vec<(String, String)>, Error> {
+        let config = try!(self.config());
+        let entries = try!(config.entries(Some("^remote\\..+\\.url$")));
+        let mut ret = Vec::new();
+        for entry in &entries {
+            let entry = try!(entry);
+            let (name, url) = match (entry.name(), entry.value()) {
+                (Some(name), Some(url)) => (name, url),
+                _ => continue,
+            };
+            let name = &name["remote.".len()..name.len() - ".url".len()];
+            if !name.is_empty() {
+                ret.push((name.to_string(), url.to_string()));
+            }
+        }
+        Ok(ret)
+    }
+
     /// Get the information for a particular remote
     pub fn find_remote(&self, name: &str) -> Result<Remote, Error> {
         let mut ret = ptr::null_mut();
@@ -574,6 +701,67 @@ impl Repository {
         }
     }
 
+    /// Retrieve and resolve the reference pointed at by the HEAD of the
+    /// linked worktree with the given name.
+    pub fn head_for_worktree(&self, name: &str) -> Result<Reference, Error> {
+        let name = try!(CString::new(name));
+        let mut ret = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_head_for_worktree(&mut ret,
+                                                            self.raw,
+                                                            name));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
+    /// Check whether the HEAD of the linked worktree with the given name is
+    /// detached.
+    pub fn head_detached_for_worktree(&self, name: &str) -> Result<bool, Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            let rc = try_call!(raw::git_repository_head_detached_for_worktree(
+                self.raw, name));
+            Ok(rc == 1)
+        }
+    }
+
+    /// List the names of the linked worktrees of this repository.
+    pub fn worktrees(&self) -> Result<StringArray, Error> {
+        let mut arr = raw::git_strarray {
+            strings: 0 as *mut *mut c_char,
+            count: 0,
+        };
+        unsafe {
+            try_call!(raw::git_worktree_list(&mut arr, self.raw));
+            Ok(Binding::from_raw(arr))
+        }
+    }
+
+    /// Look up a linked worktree of this repository by name.
+    pub fn find_worktree(&self, name: &str) -> Result<Worktree, Error> {
+        let name = try!(CString::new(name));
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_worktree_lookup(&mut raw, self.raw, name));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Add a new linked worktree with the given name at `path`.
+    ///
+    /// A new branch with the same name as the worktree is created at the
+    /// current HEAD and checked out in the worktree.
+    pub fn worktree(&self, name: &str, path: &Path) -> Result<Worktree, Error> {
+        let name = try!(CString::new(name));
+        let path = try!(path.into_c_string());
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_worktree_add(&mut raw, self.raw, name, path,
+                                            ptr::null()));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Make the repository HEAD point to the specified reference.
     ///
     /// If the provided reference points to a tree or a blob, the HEAD is
@@ -586,6 +774,11 @@ impl Repository {
     ///
     /// Otherwise, the HEAD will be detached and will directly point to the
     /// commit.
+    ///
+    /// Note that this does not touch the index or the working directory. To
+    /// switch branches, follow this with `checkout_head` (forcing the
+    /// checkout if local changes may be thrown away) so that both match the
+    /// tree of the new HEAD.
     pub fn set_head(&self, refname: &str) -> Result<(), Error> {
         let refname = try!(CString::new(refname));
         unsafe {
@@ -612,6 +805,22 @@ impl Repository {
         Ok(())
     }
 
+    /// Make the repository HEAD directly point to the commit of an annotated
+    /// commit.
+    ///
+    /// This behaves like `set_head_detached`, except that the HEAD reflog
+    /// entry describes where the commit came from, such as the name of the
+    /// reference it was looked up through, rather than just its id.
+    pub fn set_head_detached_from_annotated(&self,
+                                            commitish: &AnnotatedCommit)
+                                            -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_repository_set_head_detached_from_annotated(
+                self.raw, commitish.raw()));
+        }
+        Ok(())
+    }
+
     /// Create an iterator for the repo's references
     pub fn references(&self) -> Result<References, Error> {
         let mut ret = ptr::null_mut();
@@ -634,6 +843,53 @@ impl Repository {
         }
     }
 
+    /// Invoke a callback for each reference in the repository.
+    ///
+    /// This avoids setting up an iterator when all references are going to
+    /// be visited anyway. Return `true` from the callback to continue
+    /// iterating or `false` to stop.
+    pub fn reference_foreach<F>(&self, mut callback: F) -> Result<(), Error>
+        where F: FnMut(&Reference) -> bool
+    {
+        struct Data<'a> {
+            callback: &'a mut FnMut(&Reference) -> bool,
+        }
+        let mut data = Data { callback: &mut callback };
+        unsafe {
+            try_call!(raw::git_reference_foreach(self.raw, cb,
+                                                 &mut data as *mut _
+                                                           as *mut c_void));
+        }
+        return Ok(());
+
+        extern fn cb(reference: *mut raw::git_reference,
+                     payload: *mut c_void) -> c_int {
+            ::panic::wrap(|| unsafe {
+                // libgit2 hands ownership of each reference to the callback
+                let reference: Reference = Binding::from_raw(reference);
+                let data = &mut *(payload as *mut Data);
+                if (data.callback)(&reference) { 0 } else { 1 }
+            }).unwrap_or(1)
+        }
+    }
+
+    /// Pack all loose references in the repository into the `packed-refs`
+    /// file, as `git pack-refs --all` does.
+    ///
+    /// Repositories which accumulate many loose references can be slow to
+    /// iterate over; packing them reduces the number of files which need to
+    /// be read.
+    pub fn pack_references(&self) -> Result<(), Error> {
+        let mut refdb = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_refdb(&mut refdb, self.raw));
+            let rc = raw::git_refdb_compress(refdb);
+            raw::git_refdb_free(refdb);
+            try!(::call::try(rc));
+        }
+        Ok(())
+    }
+
     /// Load all submodules for this repository and return them.
     pub fn submodules(&self) -> Result<Vec<Submodule>, Error> {
         struct Data<'a, 'b:'a> {
@@ -704,6 +960,84 @@ impl Repository {
         Ok(ret != 0)
     }
 
+    /// Flush the cache of parsed `.gitattributes` and `.gitignore` files.
+    ///
+    /// libgit2 caches the rules it has read from attribute and ignore files
+    /// for the lifetime of the repository handle, so a long-running process
+    /// which watches the working directory may otherwise keep answering
+    /// `status_should_ignore` and `attr_get` with stale rules. After flushing,
+    /// the files are reloaded the next time they are needed.
+    pub fn refresh_attributes_cache(&self) -> Result<(), Error> {
+        unsafe {
+            raw::git_attr_cache_flush(self.raw);
+        }
+        Ok(())
+    }
+
+    /// Look up the value of a single gitattribute for a path.
+    ///
+    /// The path is relative to the working directory and does not need to
+    /// exist.
+    pub fn attr_get(&self, flags: AttrCheckFlags, path: &Path, name: &str)
+                    -> Result<AttrValue, Error> {
+        let path = try!(path.into_c_string());
+        let name = try!(CString::new(name));
+        let mut value = ptr::null();
+        unsafe {
+            try_call!(raw::git_attr_get(&mut value, self.raw, flags.bits(),
+                                        path, name));
+            Ok(attr::value_from_raw(value))
+        }
+    }
+
+    /// Look up the values of several gitattributes for a path at once.
+    ///
+    /// The returned values match `names` positionally. This is more
+    /// efficient than calling `attr_get` once for each attribute.
+    pub fn attr_get_many(&self,
+                         flags: AttrCheckFlags,
+                         path: &Path,
+                         names: &[&str]) -> Result<Vec<AttrValue>, Error> {
+        let path = try!(path.into_c_string());
+        let names = try!(names.iter().map(|n| CString::new(*n))
+                              .collect::<Result<Vec<_>, _>>());
+        let mut name_ptrs = names.iter().map(|n| n.as_ptr())
+                                 .collect::<Vec<_>>();
+        let mut values = vec![ptr::null(); names.len()];
+        unsafe {
+            try_call!(raw::git_attr_get_many(values.as_mut_ptr(),
+                                             self.raw,
+                                             flags.bits(),
+                                             path,
+                                             names.len() as size_t,
+                                             name_ptrs.as_mut_ptr()));
+            Ok(values.into_iter().map(|v| attr::value_from_raw(v)).collect())
+        }
+    }
+
+    /// Loop over all the gitattributes set for a path.
+    ///
+    /// The callback is invoked with the name and value of each attribute.
+    /// Names are passed as bytes as they are not required to be valid UTF-8.
+    /// Returning `false` stops the iteration.
+    pub fn attr_foreach<F>(&self,
+                           flags: AttrCheckFlags,
+                           path: &Path,
+                           mut f: F) -> Result<(), Error>
+        where F: FnMut(&[u8], AttrValue) -> bool
+    {
+        let path = try!(path.into_c_string());
+        unsafe {
+            let mut data = AttrForeachCbData { callback: &mut f };
+            try_call!(raw::git_attr_foreach(self.raw,
+                                            flags.bits(),
+                                            path,
+                                            attr_foreach_cb,
+                                            &mut data as *mut _ as *mut _));
+            Ok(())
+        }
+    }
+
     /// Get file status for a single file.
     ///
     /// This tries to get status for the filename that you give. If no files
@@ -772,6 +1106,40 @@ impl Repository {
         }
     }
 
+    /// Get a read-only snapshot of this repository's configuration.
+    ///
+    /// The snapshot doesn't see later changes to the underlying files, and
+    /// lets `Config::get_str` and `Config::get_bytes` be used.
+    pub fn config_snapshot(&self) -> Result<Config, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_repository_config_snapshot(&mut raw,
+                                                          self.raw()));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Get the configuration file of a single level of this repository's
+    /// configuration, such as `ConfigLevel::Local` for `.git/config`.
+    ///
+    /// Values set through the returned handle are only written to that file.
+    /// This is a shorthand for `Config::open_level` on `config`.
+    pub fn config_for_level(&self, level: ConfigLevel)
+                            -> Result<Config, Error> {
+        let config = try!(self.config());
+        config.open_level(level)
+    }
+
+    /// Set the configuration file for this repository.
+    ///
+    /// Subsequent calls to `config` will return this configuration instead of
+    /// the default one.
+    pub fn set_config(&self, config: &Config) {
+        unsafe {
+            raw::git_repository_set_config(self.raw(), config.raw());
+        }
+    }
+
     /// Write an in-memory buffer to the ODB as a blob.
     ///
     /// The Oid returned can in turn be passed to `find_blob` to get a handle to
@@ -802,6 +1170,53 @@ impl Repository {
         }
     }
 
+    /// Read a file from the working directory and write its content to the
+    /// Object Database as a loose blob.
+    ///
+    /// The path is relative to the working directory, and unlike `blob_path`
+    /// the content is run through the filters, such as line ending
+    /// normalization, configured by gitattributes for that path. Absolute
+    /// paths or paths leading outside of the working directory are rejected.
+    pub fn blob_workdir(&self, relative_path: &Path) -> Result<Oid, Error> {
+        if relative_path.is_absolute() ||
+           relative_path.components().any(|c| c == Component::ParentDir) {
+            return Err(Error::from_str("path is outside of the working \
+                                        directory"))
+        }
+        let path = try!(relative_path.into_c_string());
+        let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        unsafe {
+            try_call!(raw::git_blob_create_fromworkdir(&mut raw, self.raw(),
+                                                       path));
+            Ok(Binding::from_raw(&raw as *const _))
+        }
+    }
+
+    /// Calculate the hash of a file using repository filtering rules.
+    ///
+    /// If you simply want to calculate the hash of a file on disk with no
+    /// filters, you can just use `Oid::hash_file`. However, if you want to
+    /// hash a file in the repository and you want to apply filtering rules
+    /// (e.g. crlf filters) before generating the SHA, then use this function.
+    ///
+    /// A relative `path` is interpreted relative to the working directory.
+    /// `as_path` is the path to use to look up filtering rules; if `None` then
+    /// `path` is used for this. Passing an empty path disables filtering.
+    pub fn hashfile(&self, path: &Path, kind: ObjectType,
+                    as_path: Option<&Path>) -> Result<Oid, Error> {
+        let path = try!(path.into_c_string());
+        let as_path = match as_path {
+            Some(as_path) => Some(try!(as_path.into_c_string())),
+            None => None,
+        };
+        let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        unsafe {
+            try_call!(raw::git_repository_hashfile(&mut raw, self.raw(), path,
+                                                   kind, as_path));
+            Ok(Binding::from_raw(&raw as *const _))
+        }
+    }
+
     /// Create a stream to write blob
     ///
     /// This function may need to buffer the data on disk and will in general
@@ -847,6 +1262,16 @@ impl Repository {
         }
     }
 
+    /// Set the object database for this repository.
+    ///
+    /// Subsequent lookups and writes of objects will go through this object
+    /// database instead of the default one.
+    pub fn set_odb(&self, odb: &Odb) {
+        unsafe {
+            raw::git_repository_set_odb(self.raw(), odb.raw());
+        }
+    }
+
     /// Create a new branch pointing at a target commit
     ///
     /// A new direct reference will be created pointing to this target commit.
@@ -880,6 +1305,21 @@ impl Repository {
         }
     }
 
+    /// Return the name of the remote which the upstream of the local branch
+    /// `refname` belongs to, such as `origin`.
+    ///
+    /// Returns `Ok(None)` if the branch has no upstream configured.
+    pub fn branch_upstream_remote(&self, refname: &str)
+                                  -> Result<Option<String>, Error> {
+        let refname = try!(CString::new(refname));
+        let buf = Buf::new();
+        let rc = unsafe {
+            call!(raw::git_branch_upstream_remote(buf.raw(), self.raw,
+                                                  refname))
+        };
+        branch::upstream_string(rc, &buf)
+    }
+
     /// Create new commit in the repository
     ///
     /// If the `update_ref` is not `None`, name of the reference that will be
@@ -926,6 +1366,22 @@ impl Repository {
         }
     }
 
+    /// Lookup a reference to one of the commits in a repository by a short
+    /// hexadecimal prefix of its id.
+    ///
+    /// Returns an error with `ErrorCode::Ambiguous` if the prefix matches
+    /// more than one commit or is shorter than four characters.
+    pub fn find_commit_by_prefix(&self, prefix: &str) -> Result<Commit, Error> {
+        let id = try!(Oid::from_str(prefix));
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_commit_lookup_prefix(&mut raw, self.raw(),
+                                                    id.raw(),
+                                                    prefix.len() as size_t));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Creates a `AnnotatedCommit` from the given commit id.
     pub fn find_annotated_commit(&self, id: Oid) -> Result<AnnotatedCommit, Error> {
         unsafe {
@@ -946,6 +1402,25 @@ impl Repository {
         }
     }
 
+    /// Lookup a reference to one of the objects in a repository by a short
+    /// hexadecimal prefix of its id, the way `git show abc123` does.
+    ///
+    /// Returns an error with `ErrorCode::Ambiguous` if the prefix matches
+    /// more than one object or is shorter than four characters.
+    pub fn find_object_by_prefix(&self, prefix: &str,
+                                 kind: Option<ObjectType>)
+                                 -> Result<Object, Error> {
+        let id = try!(Oid::from_str(prefix));
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_object_lookup_prefix(&mut raw, self.raw(),
+                                                    id.raw(),
+                                                    prefix.len() as size_t,
+                                                    kind));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Create a new direct reference.
     ///
     /// This function will return an error if a reference already exists with
@@ -1069,6 +1544,34 @@ impl Repository {
         }
     }
 
+    /// Delete the reference with the given name.
+    ///
+    /// Unlike `Reference::delete` the reference doesn't need to be looked up
+    /// first, so this also works for references which are broken or whose
+    /// target is missing. `HEAD` cannot be removed.
+    pub fn reference_remove(&self, name: &str) -> Result<(), Error> {
+        if name == "HEAD" {
+            return Err(Error::from_str("cannot remove HEAD"))
+        }
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_reference_remove(self.raw(), name));
+        }
+        Ok(())
+    }
+
+    /// Start a transaction for updating several references at once.
+    ///
+    /// See `Transaction` for how references are locked, updated and then
+    /// written together.
+    pub fn transaction(&self) -> Result<Transaction, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_transaction_new(&mut raw, self.raw));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Lookup a reference to one of the objects in a repository.
     pub fn find_reference(&self, name: &str) -> Result<Reference, Error> {
         let name = try!(CString::new(name));
@@ -1079,6 +1582,25 @@ impl Repository {
         }
     }
 
+    /// Lookup a reference by its short name, e.g. `master` for
+    /// `refs/heads/master`.
+    ///
+    /// This applies the git precedence rules for the given shorthand to
+    /// determine which reference the user is referring to: `<name>`,
+    /// `refs/<name>`, `refs/tags/<name>`, `refs/heads/<name>`,
+    /// `refs/remotes/<name>` and finally `refs/remotes/<name>/HEAD`. The first
+    /// reference that exists is returned, so a tag shadows a branch of the
+    /// same name.
+    pub fn resolve_reference_from_short_name(&self, refname: &str)
+                                             -> Result<Reference, Error> {
+        let refname = try!(CString::new(refname));
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_reference_dwim(&mut raw, self.raw(), refname));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
     /// Lookup a reference by name and resolve immediately to OID.
     ///
     /// This function provides a quick way to resolve a reference name straight
@@ -1093,6 +1615,15 @@ impl Repository {
         }
     }
 
+    /// Resolve several reference names to object ids at once.
+    ///
+    /// Each name is resolved as with `refname_to_id`. The results are
+    /// returned in the same order as `names`, and a name which fails to
+    /// resolve does not prevent the others from being resolved.
+    pub fn resolve_refs(&self, names: &[&str]) -> Vec<Result<Oid, Error>> {
+        names.iter().map(|name| self.refname_to_id(name)).collect()
+    }
+
     /// Creates a git_annotated_commit from the given reference.
     pub fn reference_to_annotated_commit(&self, reference: &Reference)
                                          -> Result<AnnotatedCommit, Error> {
@@ -1110,7 +1641,8 @@ impl Repository {
     /// This looks up the user.name and user.email from the configuration and
     /// uses the current time as the timestamp, and creates a new signature
     /// based on that information. It will return `NotFound` if either the
-    /// user.name or user.email are not set.
+    /// user.name or user.email are not set, with an error message naming the
+    /// missing configuration key.
     pub fn signature(&self) -> Result<Signature<'static>, Error> {
         let mut ret = ptr::null_mut();
         unsafe {
@@ -1180,6 +1712,61 @@ impl Repository {
         }
     }
 
+    /// Count the blobs, subtrees and submodules reachable from `tree`, and
+    /// sum the sizes of the blobs.
+    ///
+    /// Every blob is looked up in the object database to find its size, so
+    /// this reads all of the objects in the tree.
+    pub fn tree_stats(&self, tree: &Tree) -> Result<TreeStats, Error> {
+        struct Data<'a> {
+            repo: &'a Repository,
+            stats: TreeStats,
+            error: Option<Error>,
+        }
+        let mut data = Data {
+            repo: self,
+            stats: TreeStats::default(),
+            error: None,
+        };
+        let rc = unsafe {
+            raw::git_tree_walk(tree.raw(), raw::GIT_TREEWALK_PRE, cb,
+                               &mut data as *mut _ as *mut c_void)
+        };
+        if let Some(e) = data.error {
+            return Err(e)
+        }
+        try!(::call::try(rc));
+        return Ok(data.stats);
+
+        extern fn cb(_root: *const c_char,
+                     entry: *const raw::git_tree_entry,
+                     payload: *mut c_void) -> c_int {
+            ::panic::wrap(|| unsafe {
+                let data = &mut *(payload as *mut Data);
+                match raw::git_tree_entry_type(entry) {
+                    raw::GIT_OBJ_TREE => data.stats.tree_count += 1,
+                    raw::GIT_OBJ_COMMIT => data.stats.submodule_count += 1,
+                    raw::GIT_OBJ_BLOB => {
+                        let id = raw::git_tree_entry_id(entry);
+                        match data.repo.find_blob(Binding::from_raw(id)) {
+                            Ok(blob) => {
+                                let size = raw::git_blob_rawsize(blob.raw());
+                                data.stats.blob_count += 1;
+                                data.stats.total_size += size as u64;
+                            }
+                            Err(e) => {
+                                data.error = Some(e);
+                                return -1
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                0
+            }).unwrap_or(-1)
+        }
+    }
+
     /// Create a new TreeBuilder, optionally initialized with the
     /// entries of the given Tree.
     ///
@@ -1250,6 +1837,21 @@ impl Repository {
         }
     }
 
+    /// Lookup an annotated tag object by the name of its reference, e.g.
+    /// `v1.0` for `refs/tags/v1.0`.
+    ///
+    /// Lightweight tags point directly at their target rather than at a tag
+    /// object, so an error is returned if `name` is a lightweight tag.
+    pub fn find_tag_by_name(&self, name: &str) -> Result<Tag, Error> {
+        let id = try!(self.refname_to_id(&format!("refs/tags/{}", name)));
+        let obj = try!(self.find_object(id, None));
+        obj.into_tag().map_err(|_| {
+            Error::from_str(&format!("tag '{}' is a lightweight tag and has \
+                                      no tag object; only annotated tags \
+                                      can be looked up", name))
+        })
+    }
+
     /// Delete an existing tag reference.
     ///
     /// The tag name will be checked for validity, see `tag` for some rules
@@ -1264,8 +1866,19 @@ impl Repository {
 
     /// Get a list with all the tags in the repository.
     ///
-    /// An optional fnmatch pattern can also be specified.
+    /// An optional fnmatch pattern can also be specified, in which case only
+    /// the tags matching it are returned (possibly none).
     pub fn tag_names(&self, pattern: Option<&str>) -> Result<StringArray, Error> {
+        self.tag_names_bytes(pattern.map(|s| s.as_bytes()))
+    }
+
+    /// Get a list with all the tags in the repository, filtered by a pattern
+    /// given as a byte array.
+    ///
+    /// Use `StringArray::iter_bytes` to read tag names which are not valid
+    /// utf-8.
+    pub fn tag_names_bytes(&self, pattern: Option<&[u8]>)
+                           -> Result<StringArray, Error> {
         let mut arr = raw::git_strarray {
             strings: 0 as *mut *mut c_char,
             count: 0,
@@ -1302,6 +1915,8 @@ impl Repository {
     /// Updates files in the working tree to match the content of the index.
     ///
     /// If the index is `None`, the repository's index will be used.
+    /// Combined with `CheckoutBuilder::force` and `CheckoutBuilder::path`
+    /// this discards working tree changes to just the listed files.
     pub fn checkout_index(&self,
                           index: Option<&mut Index>,
                           opts: Option<&mut CheckoutBuilder>) -> Result<(), Error> {
@@ -1391,30 +2006,280 @@ impl Repository {
         }
     }
 
-    /// Merge two trees, producing an index that reflects the result of
-    /// the merge. The index may be written as-is to the working directory or
-    /// checked out. If the index is to be converted to a tree, the caller
-    /// should resolve any conflicts that arose as part of the merge.
-    pub fn merge_trees(&self, ancestor_tree: &Tree, our_tree: &Tree,
-                       their_tree: &Tree, opts: Option<&MergeOptions>) -> Result<Index, Error> {
-        let mut raw = ptr::null_mut();
+    /// Merge the contents of three versions of a file, producing the merged
+    /// text along with the path and mode it should be given.
+    ///
+    /// Unlike the tree and commit merges this doesn't consult the object
+    /// database; the inputs are supplied directly.
+    pub fn merge_file(&self,
+                      ancestor: &MergeFileInput,
+                      ours: &MergeFileInput,
+                      theirs: &MergeFileInput,
+                      opts: Option<&MergeFileOptions>)
+                      -> Result<MergeFileResult, Error> {
         unsafe {
-            try_call!(raw::git_merge_trees(&mut raw, self.raw, ancestor_tree.raw(),
-                                           our_tree.raw(), their_tree.raw(),
-                                           opts.map(|o| o.raw())));
+            let mut raw = mem::zeroed();
+            try_call!(raw::git_merge_file(&mut raw,
+                                          ancestor.raw(),
+                                          ours.raw(),
+                                          theirs.raw(),
+                                          opts.map(|o| o.raw())));
             Ok(Binding::from_raw(raw))
         }
     }
 
-    /// Remove all the metadata associated with an ongoing command like merge,
-    /// revert, cherry-pick, etc. For example: MERGE_HEAD, MERGE_MSG, etc.
-    pub fn cleanup_state(&self) -> Result<(), Error> {
-        unsafe {
-            try_call!(raw::git_repository_state_cleanup(self.raw));
-        }
+    /// Merge the contents of the stage entries of an index conflict,
+    /// producing the merged text along with the path and mode it should be
+    /// given.
+    ///
+    /// Sides which are missing from the conflict, as for add/add or
+    /// modify/delete conflicts, may be passed as `None`.
+    pub fn merge_file_from_index(&self,
+                                 ancestor: Option<&IndexEntry>,
+                                 ours: Option<&IndexEntry>,
+                                 theirs: Option<&IndexEntry>,
+                                 opts: Option<&MergeFileOptions>)
+                                 -> Result<MergeFileResult, Error> {
+        let ancestor = match ancestor {
+            Some(e) => Some(try!(index::entry_to_raw(e))),
+            None => None,
+        };
+        let ours = match ours {
+            Some(e) => Some(try!(index::entry_to_raw(e))),
+            None => None,
+        };
+        let theirs = match theirs {
+            Some(e) => Some(try!(index::entry_to_raw(e))),
+            None => None,
+        };
+        unsafe {
+            let mut raw = mem::zeroed();
+            try_call!(raw::git_merge_file_from_index(
+                &mut raw,
+                self.raw,
+                ancestor.as_ref().map(|e| &e.1 as *const _),
+                ours.as_ref().map(|e| &e.1 as *const _),
+                theirs.as_ref().map(|e| &e.1 as *const _),
+                opts.map(|o| o.raw())));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Merge two trees, producing an index that reflects the result of
+    /// the merge. The index may be written as-is to the working directory or
+    /// checked out. If the index is to be converted to a tree, the caller
+    /// should resolve any conflicts that arose as part of the merge.
+    pub fn merge_trees(&self, ancestor_tree: &Tree, our_tree: &Tree,
+                       their_tree: &Tree, opts: Option<&MergeOptions>) -> Result<Index, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_merge_trees(&mut raw, self.raw, ancestor_tree.raw(),
+                                           our_tree.raw(), their_tree.raw(),
+                                           opts.map(|o| o.raw())));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Cherry-picks the given commit against `our_commit`, producing an index
+    /// that reflects the result of the cherry-pick. The index may be written
+    /// as-is to the working directory or checked out.
+    ///
+    /// The `mainline` is the parent of the commit to use as the base when
+    /// cherry-picking a merge commit, otherwise it should be 0.
+    pub fn cherrypick_commit(&self, cherrypick_commit: &Commit,
+                             our_commit: &Commit, mainline: u32,
+                             opts: Option<&MergeOptions>)
+                             -> Result<Index, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_cherrypick_commit(&mut raw, self.raw,
+                                                 cherrypick_commit.raw(),
+                                                 our_commit.raw(),
+                                                 mainline as c_uint,
+                                                 opts.map(|o| o.raw())));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Reverts the given commit against `our_commit`, producing an index that
+    /// reflects the result of the revert. The index may be written as-is to
+    /// the working directory or checked out.
+    ///
+    /// The `mainline` is the parent of the commit to use as the base when
+    /// reverting a merge commit, otherwise it should be 0.
+    pub fn revert_commit(&self, revert_commit: &Commit, our_commit: &Commit,
+                         mainline: u32, opts: Option<&MergeOptions>)
+                         -> Result<Index, Error> {
+        let mut raw = ptr::null_mut();
+        unsafe {
+            try_call!(raw::git_revert_commit(&mut raw, self.raw,
+                                             revert_commit.raw(),
+                                             our_commit.raw(),
+                                             mainline as c_uint,
+                                             opts.map(|o| o.raw())));
+            Ok(Binding::from_raw(raw))
+        }
+    }
+
+    /// Remove all the metadata associated with an ongoing command like merge,
+    /// revert, cherry-pick, etc. For example: MERGE_HEAD, MERGE_MSG, etc.
+    pub fn cleanup_state(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_repository_state_cleanup(self.raw));
+        }
         Ok(())
     }
 
+    /// Retrieve the prepared commit message left in `MERGE_MSG` by an
+    /// operation such as a merge or revert.
+    ///
+    /// Returns an error with code `ErrorCode::NotFound` if there is no
+    /// prepared message.
+    pub fn message(&self) -> Result<String, Error> {
+        let buf = Buf::new();
+        unsafe {
+            try_call!(raw::git_repository_message(buf.raw(), self.raw));
+        }
+        Ok(try!(str::from_utf8(&buf)).to_string())
+    }
+
+    /// Write a prepared commit message to `MERGE_MSG`, replacing any which
+    /// is already there.
+    ///
+    /// libgit2 has no function for this, so the file is written directly. It
+    /// lives in the directory returned by `path`, which for a linked worktree
+    /// is the worktree's own git directory rather than `commondir`, since
+    /// each worktree has its own operations in progress.
+    pub fn set_prepared_message(&self, message: &str) -> Result<(), Error> {
+        let path = self.path().join("MERGE_MSG");
+        let res = File::create(&path).and_then(|mut f| {
+            f.write_all(message.as_bytes())
+        });
+        res.map_err(|e| {
+            Error::from_str(&format!("failed to write {}: {}",
+                                     path.display(), e))
+        })
+    }
+
+    /// Remove the prepared commit message in `MERGE_MSG`.
+    pub fn remove_message(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_repository_message_remove(self.raw));
+        }
+        Ok(())
+    }
+
+    /// Abort an in-progress merge.
+    ///
+    /// This hard resets the index and working directory to `HEAD`, discarding
+    /// any conflicts or partially merged changes, and then removes the merge
+    /// metadata with `cleanup_state`.
+    pub fn abort_merge(&self) -> Result<(), Error> {
+        let head = try!(try!(self.head()).peel(ObjectType::Commit));
+        try!(self.reset(&head, ResetType::Hard, None));
+        self.cleanup_state()
+    }
+
+    /// Abort whichever operation is in progress, as reported by `state`.
+    ///
+    /// Merges, reverts and cherry-picks, including sequences of them, are
+    /// aborted as with `abort_merge`: the repository is hard reset to `HEAD`
+    /// and files such as `MERGE_HEAD`, `REVERT_HEAD` and `CHERRY_PICK_HEAD`
+    /// are removed. Nothing is done if no operation is in progress.
+    ///
+    /// An error is returned for rebases, bisections and mailbox
+    /// applications, which can't be aborted by resetting to `HEAD`.
+    pub fn abort_operation(&self) -> Result<(), Error> {
+        match self.state() {
+            RepositoryState::Clean => Ok(()),
+            RepositoryState::Merge |
+            RepositoryState::Revert |
+            RepositoryState::RevertSequence |
+            RepositoryState::CherryPick |
+            RepositoryState::CherryPickSequence => self.abort_merge(),
+            state => Err(Error::from_str(&format!("cannot abort repository \
+                                                   in state {:?}", state))),
+        }
+    }
+
+    /// Invoke a callback for each entry in the `FETCH_HEAD` file.
+    ///
+    /// The callback receives the name of the fetched reference, the url of
+    /// the remote it was fetched from, the id it pointed to and whether the
+    /// entry is to be merged by a subsequent `git pull`. The name and url are
+    /// passed as raw bytes since neither is required to be valid utf-8, and
+    /// entries recorded without one are given an empty one. Return `true` to
+    /// continue iterating or `false` to stop.
+    ///
+    /// An error with code `ErrorCode::NotFound` is returned if there is no
+    /// `FETCH_HEAD` file.
+    pub fn fetchhead_foreach<F>(&self, mut callback: F) -> Result<(), Error>
+        where F: FnMut(&[u8], &[u8], Oid, bool) -> bool
+    {
+        struct Data<'a> {
+            callback: &'a mut FnMut(&[u8], &[u8], Oid, bool) -> bool,
+        }
+        let mut data = Data { callback: &mut callback };
+        unsafe {
+            let payload = &mut data as *mut _ as *mut c_void;
+            try_call!(raw::git_repository_fetchhead_foreach(self.raw, cb, payload));
+        }
+        return Ok(());
+
+        extern fn cb(ref_name: *const c_char,
+                     remote_url: *const c_char,
+                     oid: *const raw::git_oid,
+                     is_merge: c_uint,
+                     payload: *mut c_void) -> c_int {
+            ::panic::wrap(|| unsafe {
+                let data = &mut *(payload as *mut Data);
+                let ref_name = if ref_name.is_null() {
+                    &[][..]
+                } else {
+                    CStr::from_ptr(ref_name).to_bytes()
+                };
+                let remote_url = if remote_url.is_null() {
+                    &[][..]
+                } else {
+                    CStr::from_ptr(remote_url).to_bytes()
+                };
+                let oid = Binding::from_raw(oid);
+                if (data.callback)(ref_name, remote_url, oid, is_merge != 0) {
+                    0
+                } else {
+                    1
+                }
+            }).unwrap_or(1)
+        }
+    }
+
+    /// Invoke a callback for each commit id in the `MERGE_HEAD` file, which
+    /// lists the commits being merged during a conflicted merge.
+    ///
+    /// Return `true` to continue iterating or `false` to stop. An error with
+    /// code `ErrorCode::NotFound` is returned if there is no `MERGE_HEAD`
+    /// file.
+    pub fn mergehead_foreach<F>(&self, mut callback: F) -> Result<(), Error>
+        where F: FnMut(Oid) -> bool
+    {
+        struct Data<'a> {
+            callback: &'a mut FnMut(Oid) -> bool,
+        }
+        let mut data = Data { callback: &mut callback };
+        unsafe {
+            let payload = &mut data as *mut _ as *mut c_void;
+            try_call!(raw::git_repository_mergehead_foreach(self.raw, cb, payload));
+        }
+        return Ok(());
+
+        extern fn cb(oid: *const raw::git_oid, payload: *mut c_void) -> c_int {
+            ::panic::wrap(|| unsafe {
+                let data = &mut *(payload as *mut Data);
+                if (data.callback)(Binding::from_raw(oid)) { 0 } else { 1 }
+            }).unwrap_or(1)
+        }
+    }
+
     /// Analyzes the given branch(es) and determines the opportunities for
     /// merging them into the HEAD of the repository.
     pub fn merge_analysis(&self,
@@ -1465,6 +2330,9 @@ impl Repository {
     }
 
     /// Get the default notes reference for this repository
+    ///
+    /// This is the value of the `core.notesRef` config variable, or
+    /// "refs/notes/commits" if it is unset.
     pub fn note_default_ref(&self) -> Result<String, Error> {
         let ret = Buf::new();
         unsafe {
@@ -1473,6 +2341,20 @@ impl Repository {
         Ok(str::from_utf8(&ret).unwrap().to_string())
     }
 
+    /// List the names of all the notes references in this repository, i.e.
+    /// those under `refs/notes/`.
+    ///
+    /// Reference names which are not valid utf-8 are skipped.
+    pub fn notes_refs(&self) -> Result<Vec<String>, Error> {
+        let mut ret = Vec::new();
+        for reference in try!(self.references_glob("refs/notes/*")) {
+            if let Some(name) = try!(reference).name() {
+                ret.push(name.to_string());
+            }
+        }
+        Ok(ret)
+    }
+
     /// Creates a new iterator for notes in this repository.
     ///
     /// The `notes_ref` argument is the canonical name of the reference to use,
@@ -1535,6 +2417,67 @@ impl Repository {
         }
     }
 
+    /// Find the commits which changed the file at `path`, newest first.
+    ///
+    /// History is walked from `start`, or from `HEAD` if it's `None`, and
+    /// each commit is compared against its first parent. When a commit turns
+    /// out to have renamed the file, the walk carries on following its old
+    /// path.
+    pub fn file_history(&self, path: &Path, start: Option<Oid>)
+                        -> Result<Vec<Oid>, Error> {
+        let mut walk = try!(self.revwalk());
+        walk.set_sorting(::SORT_TOPOLOGICAL);
+        match start {
+            Some(id) => try!(walk.push(id)),
+            None => try!(walk.push_head()),
+        }
+
+        let mut path = path.to_path_buf();
+        let mut history = Vec::new();
+        for id in walk {
+            let commit = try!(self.find_commit(try!(id)));
+            let tree = try!(commit.tree());
+            let parent = if commit.parent_count() > 0 {
+                Some(try!(try!(commit.parent(0)).tree()))
+            } else {
+                None
+            };
+
+            let status = {
+                let mut opts = DiffOptions::new();
+                opts.pathspec(&*path).disable_pathspec_match(true);
+                let diff = try!(self.diff_tree_to_tree(parent.as_ref(),
+                                                       Some(&tree),
+                                                       Some(&mut opts)));
+                let delta = diff.deltas().next();
+                delta.map(|d| d.status())
+            };
+            match status {
+                Some(status) => {
+                    history.push(commit.id());
+                    if status != Delta::Added || parent.is_none() {
+                        continue
+                    }
+                }
+                None => continue,
+            }
+
+            // The file appeared in this commit, so check whether it was
+            // renamed from somewhere else.
+            let mut diff = try!(self.diff_tree_to_tree(parent.as_ref(),
+                                                       Some(&tree), None));
+            try!(diff.find_similar(Some(DiffFindOptions::new().renames(true))));
+            let old = diff.deltas().find(|d| {
+                d.status() == Delta::Renamed &&
+                    d.new_file().path() == Some(&*path)
+            }).and_then(|d| d.old_file().path().map(|p| p.to_path_buf()));
+            if let Some(old) = old {
+                path = old;
+            }
+        }
+        Ok(history)
+    }
+
     /// Get the blame for a single file.
     pub fn blame_file(&self, path: &Path, opts: Option<&mut BlameOptions>)
                       -> Result<Blame, Error> {
@@ -1573,6 +2516,40 @@ impl Repository {
         }
     }
 
+    /// Find a merge base given a list of commits
+    ///
+    /// This behaves like `git merge-base` given more than two commits: the
+    /// result is a merge base between the first commit and a hypothetical
+    /// merge of all the others. At least two commits must be given.
+    pub fn merge_base_many(&self, oids: &[Oid]) -> Result<Oid, Error> {
+        let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        let input = oids.iter().map(|id| unsafe { *id.raw() })
+                        .collect::<Vec<_>>();
+        unsafe {
+            try_call!(raw::git_merge_base_many(&mut raw, self.raw,
+                                               input.len() as size_t,
+                                               input.as_ptr()));
+            Ok(Binding::from_raw(&raw as *const _))
+        }
+    }
+
+    /// Find a merge base in preparation for an octopus merge of a list of
+    /// commits
+    ///
+    /// This behaves like `git merge-base --octopus`: the result is a common
+    /// ancestor of all the given commits. At least two commits must be given.
+    pub fn merge_base_octopus(&self, oids: &[Oid]) -> Result<Oid, Error> {
+        let mut raw = raw::git_oid { id: [0; raw::GIT_OID_RAWSZ] };
+        let input = oids.iter().map(|id| unsafe { *id.raw() })
+                        .collect::<Vec<_>>();
+        unsafe {
+            try_call!(raw::git_merge_base_octopus(&mut raw, self.raw,
+                                                  input.len() as size_t,
+                                                  input.as_ptr()));
+            Ok(Binding::from_raw(&raw as *const _))
+        }
+    }
+
 
     /// Count the number of unique commits between two commit objects
     ///
@@ -1592,6 +2569,18 @@ impl Repository {
         }
     }
 
+    /// Count the commits reachable from `newer` but not from `older`.
+    ///
+    /// This is the number of commits in the range `older..newer`, which is
+    /// what a bisect driver needs to pick a midpoint.
+    pub fn commits_between(&self, newer: Oid, older: Oid)
+                           -> Result<usize, Error> {
+        let mut walk = try!(self.revwalk());
+        try!(walk.push(newer));
+        try!(walk.hide(older));
+        walk.count()
+    }
+
     /// Determine if a commit is the descendant of another commit
     pub fn graph_descendant_of(&self, commit: Oid, ancestor: Oid)
                                -> Result<bool, Error> {
@@ -1603,6 +2592,22 @@ impl Repository {
         }
     }
 
+    /// Determine if a commit is reachable from any of a list of commits, by
+    /// following parent edges.
+    ///
+    /// A commit is considered reachable from itself. Returns `false` if
+    /// `descendants` is empty.
+    pub fn graph_reachable_from_any(&self, commit: Oid, descendants: &[Oid])
+                                    -> Result<bool, Error> {
+        for &descendant in descendants {
+            if descendant == commit ||
+               try!(self.graph_descendant_of(descendant, commit)) {
+                return Ok(true)
+            }
+        }
+        Ok(false)
+    }
+
     /// Read the reflog for the given reference
     ///
     /// If there is no reflog file for the given reference yet, an empty reflog
@@ -1846,6 +2851,10 @@ impl Repository {
     }
 
     /// Apply a single stashed state from the stash list.
+    ///
+    /// If the stashed changes conflict with the current state of the
+    /// repository the stash is left untouched and an error is returned, with
+    /// `ErrorCode::MergeConflict` when the conflict is in the index.
     pub fn stash_apply(&mut self,
                        index: usize,
                        opts: Option<&mut StashApplyOptions>)
@@ -2063,9 +3072,13 @@ impl RepositoryInitOptions {
 mod tests {
     use std::ffi::OsStr;
     use std::fs;
+    use std::io::prelude::*;
     use std::path::Path;
     use tempdir::TempDir;
-    use {Repository, Oid, ObjectType, ResetType};
+    use {Repository, Oid, ObjectType, ResetType, Commit, MergeOptions, FileFavor, Index};
+    use {AttrValue, ATTR_CHECK_FILE_THEN_INDEX};
+    use {MergeFileInput, MergeFileOptions, ErrorCode, RepositoryState};
+    use ConfigLevel;
     use build::CheckoutBuilder;
 
     #[test]
@@ -2223,6 +3236,97 @@ mod tests {
         assert!(!repo.graph_descendant_of(head_parent_id, head_id).unwrap());
     }
 
+    #[test]
+    fn smoke_namespace() {
+        let (td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        assert_eq!(repo.references().unwrap().count(), 1);
+
+        let repo = Repository::open(td.path()).unwrap();
+        assert!(repo.namespace().is_none());
+        repo.set_namespace("ns").unwrap();
+        assert_eq!(repo.namespace(), Some("ns"));
+        assert_eq!(repo.references().unwrap().count(), 0);
+        assert!(repo.find_reference("refs/heads/master").is_err());
+
+        repo.reference("refs/heads/scoped", id, false, "").unwrap();
+        let names: Vec<String> = repo.references().unwrap().map(|r| {
+            r.unwrap().name().unwrap().to_string()
+        }).collect();
+        assert_eq!(names, vec!["refs/heads/scoped".to_string()]);
+
+        repo.remove_namespace().unwrap();
+        assert!(repo.namespace().is_none());
+
+        let repo = Repository::open(td.path()).unwrap();
+        assert!(repo.find_reference("refs/heads/scoped").is_err());
+        let scoped = "refs/namespaces/ns/refs/heads/scoped";
+        assert_eq!(repo.find_reference(scoped).unwrap().target(), Some(id));
+    }
+
+    #[test]
+    fn smoke_hashfile() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(fs::File::create(&td.path().join(".gitattributes")))
+            .write_all(b"* text=auto\n"));
+        t!(t!(fs::File::create(&td.path().join("foo"))).write_all(b"a\r\nb\r\n"));
+
+        let path = Path::new("foo");
+        let id = t!(repo.hashfile(path, ObjectType::Blob, None));
+        assert_eq!(id, t!(Oid::hash_object(ObjectType::Blob, b"a\nb\n")));
+
+        let mut index = t!(repo.index());
+        t!(index.add_path(path));
+        assert_eq!(index.get_path(path, 0).unwrap().id, id);
+
+        let raw = t!(repo.hashfile(path, ObjectType::Blob, Some(Path::new(""))));
+        assert_eq!(raw, t!(Oid::hash_object(ObjectType::Blob, b"a\r\nb\r\n")));
+        assert_eq!(raw, t!(Oid::hash_file(ObjectType::Blob, td.path().join(path))));
+    }
+
+    #[test]
+    fn smoke_set_index() {
+        let (td, repo) = ::test::repo_init();
+        t!(fs::File::create(&td.path().join("foo")));
+
+        let mut index = t!(Index::new());
+        repo.set_index(&mut index);
+        let mut index = t!(repo.index());
+        assert_eq!(index.len(), 0);
+        t!(index.add_path(Path::new("foo")));
+        assert!(t!(repo.index()).get_path(Path::new("foo"), 0).is_some());
+
+        let disk = t!(Index::open(&repo.path().join("index")));
+        assert_eq!(disk.len(), 0);
+        assert!(disk.get_path(Path::new("foo"), 0).is_none());
+
+        let config = t!(repo.config());
+        repo.set_config(&config);
+        let odb = t!(repo.odb());
+        repo.set_odb(&odb);
+        let id = t!(repo.blob(b"foo"));
+        assert!(repo.find_blob(id).is_ok());
+    }
+
+    #[test]
+    fn smoke_from_odb() {
+        let (_td, repo) = ::test::repo_init();
+        let odb = t!(repo.odb());
+        let id = {
+            let mut writer = t!(odb.writer(5, ObjectType::Blob));
+            t!(writer.write_all(b"hello"));
+            t!(writer.finalize())
+        };
+
+        let wrapped = t!(Repository::from_odb(odb));
+        assert_eq!(t!(wrapped.find_blob(id)).content(), b"hello");
+        assert!(wrapped.workdir().is_none());
+        assert!(wrapped.head().is_err());
+
+        let id = t!(wrapped.blob(b"written through the wrapper"));
+        assert!(repo.find_blob(id).is_ok());
+    }
+
     #[test]
     fn smoke_reference_has_log_ensure_log() {
         let (_td, repo) = ::test::repo_init();
@@ -2235,6 +3339,13 @@ mod tests {
         assert_eq!(repo.reference_has_log("NOT_HEAD").unwrap(), false);
         assert!(repo.reference_ensure_log("NOT_HEAD").is_ok());
         assert_eq!(repo.reference_has_log("NOT_HEAD").unwrap(), true);
+
+        repo.reference("refs/foo/bar", master_oid, false, "custom").unwrap();
+        assert_eq!(repo.reference_has_log("refs/foo/bar").unwrap(), false);
+        repo.reference_ensure_log("refs/foo/bar").unwrap();
+        assert_eq!(repo.reference_has_log("refs/foo/bar").unwrap(), true);
+        // ensuring a log which already exists is not an error
+        repo.reference_ensure_log("refs/foo/bar").unwrap();
     }
 
     #[test]
@@ -2250,6 +3361,72 @@ mod tests {
         assert!(repo.set_head("*").is_err());
     }
 
+    #[test]
+    fn smoke_switch_branch() {
+        let (td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let path = td.path().join("foo");
+        t!(t!(fs::File::create(&path)).write_all(b"one"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("foo")));
+        t!(index.write());
+        let tree = t!(repo.find_tree(t!(index.write_tree())));
+        let parent = t!(repo.find_commit(t!(repo.refname_to_id("HEAD"))));
+        let one = t!(repo.commit(Some("HEAD"), &sig, &sig, "one", &tree,
+                                 &[&parent]));
+
+        let two_blob = t!(repo.blob(b"two"));
+        let mut builder = t!(repo.treebuilder(Some(&tree)));
+        t!(builder.insert("foo", two_blob, 0o100644));
+        let tree = t!(repo.find_tree(t!(builder.write())));
+        let parent = t!(repo.find_commit(one));
+        t!(repo.commit(Some("refs/heads/two"), &sig, &sig, "two", &tree,
+                       &[&parent]));
+
+        let read = |path: &Path| {
+            let mut s = String::new();
+            t!(t!(fs::File::open(path)).read_to_string(&mut s));
+            s
+        };
+
+        t!(repo.set_head("refs/heads/two"));
+        t!(repo.checkout_head(Some(CheckoutBuilder::new().force())));
+        assert_eq!(read(&path), "two");
+        let index = t!(repo.index());
+        assert_eq!(index.get_path(Path::new("foo"), 0).unwrap().id, two_blob);
+        assert!(t!(repo.statuses(None)).is_empty());
+
+        t!(repo.set_head("refs/heads/master"));
+        t!(repo.checkout_head(Some(CheckoutBuilder::new().force())));
+        assert_eq!(read(&path), "one");
+        let index = t!(repo.index());
+        let one_blob = t!(repo.find_commit(one)).tree().unwrap()
+                           .get_name("foo").unwrap().id();
+        assert_eq!(index.get_path(Path::new("foo"), 0).unwrap().id, one_blob);
+        assert!(t!(repo.statuses(None)).is_empty());
+    }
+
+    #[test]
+    fn smoke_resolve_reference_from_short_name() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+
+        let reference = repo.resolve_reference_from_short_name("master")
+                            .unwrap();
+        assert_eq!(reference.name(), Some("refs/heads/master"));
+        assert_eq!(reference.target(), Some(head));
+        assert!(repo.resolve_reference_from_short_name("missing").is_err());
+
+        let commit = repo.find_commit(head).unwrap();
+        repo.branch("dup", &commit, false).unwrap();
+        repo.reference("refs/tags/dup", head, false, "").unwrap();
+        let reference = repo.resolve_reference_from_short_name("dup").unwrap();
+        assert_eq!(reference.name(), Some("refs/tags/dup"));
+        let reference = repo.resolve_reference_from_short_name("heads/dup")
+                            .unwrap();
+        assert_eq!(reference.name(), Some("refs/heads/dup"));
+    }
+
     #[test]
     fn smoke_set_head_detached() {
         let (_td, repo) = ::test::repo_init();
@@ -2262,6 +3439,25 @@ mod tests {
         assert_eq!(repo.head().unwrap().target().unwrap(), master_oid);
     }
 
+    #[test]
+    fn smoke_set_head_detached_from_annotated() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+        let obj = repo.find_object(head, None).unwrap();
+        let sig = repo.signature().unwrap();
+        repo.tag("v1.0", &obj, &sig, "release", false).unwrap();
+
+        let tag = repo.find_reference("refs/tags/v1.0").unwrap();
+        let annotated = repo.reference_to_annotated_commit(&tag).unwrap();
+        repo.set_head_detached_from_annotated(&annotated).unwrap();
+        assert_eq!(repo.head().unwrap().name(), Some("HEAD"));
+        assert_eq!(repo.head().unwrap().target().unwrap(), head);
+
+        let reflog = repo.reflog("HEAD").unwrap();
+        let message = reflog.get(0).unwrap().message().unwrap().to_string();
+        assert!(message.contains("v1.0"), "bad message: {}", message);
+    }
+
     /// create an octopus:
     ///   /---o2-o4
     /// o1      X
@@ -2346,6 +3542,165 @@ mod tests {
 	    assert_eq!(merge_bases.len(), 2);
     }
 
+    #[test]
+    fn smoke_merge_base_octopus() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let root = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(root.tree());
+        let tips = ["a", "b", "c"].iter().map(|name| {
+            t!(repo.commit(None, &sig, &sig, name, &tree, &[&root]))
+        }).collect::<Vec<_>>();
+
+        assert_eq!(t!(repo.merge_base_octopus(&tips)), root.id());
+        assert_eq!(t!(repo.merge_base_many(&tips)), root.id());
+        assert_eq!(t!(repo.merge_base_octopus(&[tips[0], root.id()])),
+                   root.id());
+
+        assert!(repo.merge_base_octopus(&[]).is_err());
+        assert!(repo.merge_base_octopus(&tips[..1]).is_err());
+        assert!(repo.merge_base_many(&tips[..1]).is_err());
+    }
+
+    #[test]
+    fn smoke_merge_commits_and_trees() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let base = repo.find_commit(head).unwrap();
+
+        let commit_with = |name: &str, contents: &[u8]| {
+            let blob = repo.blob(contents).unwrap();
+            let mut builder = repo.treebuilder(Some(&base.tree().unwrap()))
+                                  .unwrap();
+            builder.insert(name, blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            let id = repo.commit(None, &sig, &sig, name, &tree,
+                                 &[&base]).unwrap();
+            repo.find_commit(id).unwrap()
+        };
+        let ours = commit_with("file_a", b"a\n");
+        let theirs = commit_with("file_b", b"b\n");
+
+        let index = repo.merge_commits(&ours, &theirs, None).unwrap();
+        assert!(!index.has_conflicts());
+        assert_eq!(index.conflicts().unwrap().count(), 0);
+        assert!(index.get_path(Path::new("file_a"), 0).is_some());
+        assert!(index.get_path(Path::new("file_b"), 0).is_some());
+
+        let index = repo.merge_trees(&base.tree().unwrap(),
+                                     &ours.tree().unwrap(),
+                                     &theirs.tree().unwrap(),
+                                     None).unwrap();
+        assert!(!index.has_conflicts());
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn merge_file_favor() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+
+        let commit_with = |contents: &[u8], parents: &[&Commit]| {
+            let blob = repo.blob(contents).unwrap();
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert("file", blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            let id = repo.commit(None, &sig, &sig, "msg", &tree,
+                                 parents).unwrap();
+            repo.find_commit(id).unwrap()
+        };
+        let base = commit_with(b"base\n", &[]);
+        let ours = commit_with(b"ours\n", &[&base]);
+        let theirs = commit_with(b"theirs\n", &[&base]);
+
+        let index = repo.merge_commits(&ours, &theirs, None).unwrap();
+        assert!(index.has_conflicts());
+        for conflict in index.conflicts().unwrap() {
+            let conflict = conflict.unwrap();
+            assert_eq!(conflict.ancestor.unwrap().stage(), 1);
+            assert_eq!(conflict.our.unwrap().stage(), 2);
+            assert_eq!(conflict.their.unwrap().stage(), 3);
+        }
+
+        let mut opts = MergeOptions::new();
+        opts.fail_on_conflict(true);
+        assert!(repo.merge_commits(&ours, &theirs, Some(&opts)).is_err());
+
+        let mut opts = MergeOptions::new();
+        opts.file_favor(FileFavor::Ours);
+        let index = repo.merge_commits(&ours, &theirs, Some(&opts)).unwrap();
+        assert!(!index.has_conflicts());
+        let entry = index.get_path(Path::new("file"), 0).unwrap();
+        assert_eq!(repo.find_blob(entry.id).unwrap().content(), b"ours\n");
+
+        let index = repo.cherrypick_commit(&theirs, &ours, 0, Some(&opts))
+                        .unwrap();
+        let entry = index.get_path(Path::new("file"), 0).unwrap();
+        assert_eq!(repo.find_blob(entry.id).unwrap().content(), b"ours\n");
+
+        let index = repo.revert_commit(&ours, &ours, 0, None).unwrap();
+        let entry = index.get_path(Path::new("file"), 0).unwrap();
+        assert_eq!(repo.find_blob(entry.id).unwrap().content(), b"base\n");
+    }
+
+    #[test]
+    fn smoke_abort_merge() {
+        let (td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let path = Path::new("file");
+        let commit_file = |contents: &[u8], refname: &str| {
+            let parent = t!(t!(repo.find_reference(refname)).peel_to_commit());
+            t!(t!(fs::File::create(td.path().join(path))).write_all(contents));
+            let mut index = t!(repo.index());
+            t!(index.add_path(path));
+            t!(index.write());
+            let tree = t!(repo.find_tree(t!(index.write_tree())));
+            t!(repo.commit(Some(refname), &sig, &sig, "msg", &tree, &[&parent]))
+        };
+        let head = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.branch("theirs", &head, false));
+        let theirs = commit_file(b"theirs\n", "refs/heads/theirs");
+        t!(repo.reset(head.as_object(), ResetType::Hard, None));
+        let ours = commit_file(b"ours\n", "HEAD");
+
+        assert!(repo.abort_operation().is_ok());
+        let theirs = t!(repo.find_annotated_commit(theirs));
+        t!(repo.merge(&[&theirs], None, None));
+        assert_eq!(repo.state(), RepositoryState::Merge);
+        assert!(t!(repo.index()).has_conflicts());
+        assert!(repo.path().join("MERGE_HEAD").exists());
+
+        t!(repo.abort_merge());
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert!(!repo.path().join("MERGE_HEAD").exists());
+        assert!(!t!(repo.index()).has_conflicts());
+        assert_eq!(t!(repo.head()).target(), Some(ours));
+        let mut contents = String::new();
+        t!(t!(fs::File::open(td.path().join(path)))
+            .read_to_string(&mut contents));
+        assert_eq!(contents, "ours\n");
+        assert!(t!(repo.statuses(None)).is_empty());
+
+        t!(fs::File::create(repo.path().join("BISECT_LOG")));
+        assert_eq!(repo.state(), RepositoryState::Bisect);
+        assert!(repo.abort_operation().is_err());
+    }
+
+    #[test]
+    fn smoke_rev_exists() {
+        let (_td, repo) = graph_repo_init();
+        assert!(repo.rev_exists("HEAD"));
+        assert!(repo.rev_exists("HEAD~1"));
+        assert!(repo.rev_exists("master^{tree}"));
+        assert!(!repo.rev_exists("HEAD~5"));
+        assert!(!repo.rev_exists("nope~3"));
+        assert!(!repo.rev_exists("HEAD^{"));
+        assert!(t!(repo.try_rev_exists("HEAD")));
+        assert!(!t!(repo.try_rev_exists("nope~3")));
+        assert!(repo.try_rev_exists("nul\0byte").is_err());
+    }
+
     #[test]
     fn smoke_revparse_ext() {
         let (_td, repo) = graph_repo_init();
@@ -2356,6 +3711,7 @@ mod tests {
             let (obj, reference) = repo.revparse_ext(short_refname).unwrap();
             let expected_obj = repo.revparse_single(expected_refname).unwrap();
             assert_eq!(obj.id(), expected_obj.id());
+            assert_eq!(obj.kind(), Some(ObjectType::Commit));
             assert_eq!(reference.unwrap().name().unwrap(), expected_refname);
         }
         {
@@ -2366,5 +3722,508 @@ mod tests {
             let (_obj, reference) = repo.revparse_ext("HEAD^").unwrap();
             assert!(reference.is_none());
         }
+        {
+            let (obj, reference) = repo.revparse_ext("HEAD~1").unwrap();
+            assert_eq!(obj.kind(), Some(ObjectType::Commit));
+            assert!(reference.is_none());
+
+            let (same, reference) = repo.revparse_ext(&obj.id().to_string())
+                                        .unwrap();
+            assert_eq!(same.id(), obj.id());
+            assert!(reference.is_none());
+        }
+    }
+
+    #[test]
+    fn smoke_attr_get_many() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(fs::File::create(td.path().join(".gitattributes")))
+            .write_all(b"foo.txt text eol=crlf -diff \xff\n"));
+        let path = Path::new("foo.txt");
+
+        let values = t!(repo.attr_get_many(ATTR_CHECK_FILE_THEN_INDEX, path,
+                                           &["text", "eol", "diff", "missing"]));
+        assert_eq!(values, vec![AttrValue::True, AttrValue::String("crlf"),
+                                AttrValue::False, AttrValue::Unspecified]);
+        assert_eq!(t!(repo.attr_get(ATTR_CHECK_FILE_THEN_INDEX, path, "eol")),
+                   AttrValue::String("crlf"));
+
+        let mut names = Vec::new();
+        t!(repo.attr_foreach(ATTR_CHECK_FILE_THEN_INDEX, path, |name, _| {
+            names.push(name.to_vec());
+            true
+        }));
+        names.sort();
+        assert_eq!(names, vec![&b"diff"[..], b"eol", b"text", b"\xff"]);
+    }
+
+    #[test]
+    fn smoke_merge_file() {
+        let (_td, repo) = ::test::repo_init();
+
+        let mut ancestor = MergeFileInput::new();
+        ancestor.path("file.txt").mode(0o100644)
+                .content(b"one\ntwo\nthree\nfour\nfive\n");
+        let mut ours = MergeFileInput::new();
+        ours.path("file.txt").mode(0o100644)
+            .content(b"ONE\ntwo\nthree\nfour\nfive\n");
+        let mut theirs = MergeFileInput::new();
+        theirs.path("file.txt").mode(0o100644)
+              .content(b"one\ntwo\nthree\nfour\nFIVE\n");
+
+        let result = t!(repo.merge_file(&ancestor, &ours, &theirs, None));
+        assert!(result.automergeable());
+        assert_eq!(result.path(), Some("file.txt"));
+        assert_eq!(result.mode(), 0o100644);
+        assert_eq!(result.content(), &b"ONE\ntwo\nthree\nfour\nFIVE\n"[..]);
+
+        let mut theirs = MergeFileInput::new();
+        theirs.path("file.txt").mode(0o100644)
+              .content(b"uno\ntwo\nthree\nfour\nfive\n");
+        let mut opts = MergeFileOptions::new();
+        opts.our_label("ours").their_label("theirs").marker_size(3);
+        let result = t!(repo.merge_file(&ancestor, &ours, &theirs, Some(&opts)));
+        assert!(!result.automergeable());
+        let content = String::from_utf8(result.content().to_vec()).unwrap();
+        assert!(content.contains("<<< ours\n"));
+        assert!(content.contains(">>> theirs\n"));
+    }
+
+    #[test]
+    fn smoke_merge_file_from_index() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+
+        let commit_with = |contents: &[u8], parents: &[&Commit]| {
+            let blob = repo.blob(contents).unwrap();
+            let mut builder = repo.treebuilder(None).unwrap();
+            builder.insert("file", blob, 0o100644).unwrap();
+            let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+            let id = repo.commit(None, &sig, &sig, "msg", &tree,
+                                 parents).unwrap();
+            repo.find_commit(id).unwrap()
+        };
+        let base = commit_with(b"base\n", &[]);
+        let ours = commit_with(b"ours\n", &[&base]);
+        let theirs = commit_with(b"theirs\n", &[&base]);
+
+        let index = repo.merge_commits(&ours, &theirs, None).unwrap();
+        let conflict = index.conflicts().unwrap().next().unwrap().unwrap();
+        let result = t!(repo.merge_file_from_index(conflict.ancestor.as_ref(),
+                                                   conflict.our.as_ref(),
+                                                   conflict.their.as_ref(),
+                                                   None));
+        assert!(!result.automergeable());
+        assert_eq!(result.path(), Some("file"));
+        let content = String::from_utf8(result.content().to_vec()).unwrap();
+        assert!(content.contains("<<<<<<< file\nours\n=======\ntheirs\n"));
+
+        let result = t!(repo.merge_file_from_index(None,
+                                                   conflict.our.as_ref(),
+                                                   conflict.their.as_ref(),
+                                                   None));
+        assert!(!result.automergeable());
+
+        let mut opts = MergeFileOptions::new();
+        opts.favor(FileFavor::Theirs);
+        let result = t!(repo.merge_file_from_index(conflict.ancestor.as_ref(),
+                                                   conflict.our.as_ref(),
+                                                   conflict.their.as_ref(),
+                                                   Some(&opts)));
+        assert!(result.automergeable());
+        assert_eq!(result.content(), &b"theirs\n"[..]);
+    }
+
+    #[test]
+    fn smoke_find_by_prefix() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head()).target().unwrap();
+        let hex = head.to_string();
+
+        let commit = t!(repo.find_commit_by_prefix(&hex[..4]));
+        assert_eq!(commit.id(), head);
+        let obj = t!(repo.find_object_by_prefix(&hex[..4], None));
+        assert_eq!(obj.id(), head);
+        assert_eq!(obj.kind(), Some(ObjectType::Commit));
+        assert!(repo.find_object_by_prefix(&hex[..4],
+                                           Some(ObjectType::Blob)).is_err());
+
+        let err = repo.find_object_by_prefix(&hex[..2], None).err().unwrap();
+        assert_eq!(err.code(), ErrorCode::Ambiguous);
+        let err = repo.find_commit_by_prefix(&hex[..2]).err().unwrap();
+        assert_eq!(err.code(), ErrorCode::Ambiguous);
+    }
+
+    #[test]
+    fn smoke_checkout_index() {
+        let (td, repo) = ::test::repo_init();
+        let foo = td.path().join("foo");
+        let bar = td.path().join("bar");
+        t!(t!(fs::File::create(&foo)).write_all(b"staged foo\n"));
+        t!(t!(fs::File::create(&bar)).write_all(b"staged bar\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("foo")));
+        t!(index.add_path(Path::new("bar")));
+        t!(index.write());
+
+        t!(t!(fs::File::create(&foo)).write_all(b"edited foo\n"));
+        t!(t!(fs::File::create(&bar)).write_all(b"edited bar\n"));
+
+        let mut opts = CheckoutBuilder::new();
+        opts.force().path("foo");
+        t!(repo.checkout_index(None, Some(&mut opts)));
+
+        let mut contents = String::new();
+        t!(t!(fs::File::open(&foo)).read_to_string(&mut contents));
+        assert_eq!(contents, "staged foo\n");
+        contents.clear();
+        t!(t!(fs::File::open(&bar)).read_to_string(&mut contents));
+        assert_eq!(contents, "edited bar\n");
+    }
+
+    #[test]
+    fn smoke_blob_workdir() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(fs::File::create(td.path().join(".gitattributes")))
+            .write_all(b"*.txt text\n"));
+        t!(t!(fs::File::create(td.path().join("foo.txt")))
+            .write_all(b"one\r\ntwo\r\n"));
+
+        let id = t!(repo.blob_workdir(Path::new("foo.txt")));
+        assert_eq!(id, t!(repo.blob(b"one\ntwo\n")));
+        assert_eq!(t!(repo.blob_path(&td.path().join("foo.txt"))),
+                   t!(repo.blob(b"one\r\ntwo\r\n")));
+
+        assert!(repo.blob_workdir(Path::new("missing.txt")).is_err());
+        assert!(repo.blob_workdir(Path::new("../foo.txt")).is_err());
+        assert!(repo.blob_workdir(&td.path().join("foo.txt")).is_err());
+    }
+
+    #[test]
+    fn smoke_signature() {
+        let td = TempDir::new("test").unwrap();
+        let repo = Repository::init(td.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Jane Doe").unwrap();
+            config.set_str("user.email", "jane@example.com").unwrap();
+        }
+        let sig = repo.signature().unwrap();
+        assert_eq!(sig.name(), Some("Jane Doe"));
+        assert_eq!(sig.email(), Some("jane@example.com"));
+        assert!(sig.when().seconds() > 0);
+    }
+
+    #[test]
+    fn smoke_worktree() {
+        let (td, repo) = ::test::repo_init();
+        let wtdir = TempDir::new("worktree").unwrap();
+        let wtpath = wtdir.path().join("wt");
+        let wt = t!(repo.worktree("wt", &wtpath));
+        t!(wt.validate());
+        let names = t!(repo.worktrees());
+        assert_eq!(names.iter().collect::<Vec<_>>(), vec![Some("wt")]);
+
+        let head = t!(repo.head_for_worktree("wt"));
+        assert_eq!(head.name(), Some("refs/heads/wt"));
+        assert!(!t!(repo.head_detached_for_worktree("wt")));
+
+        assert_eq!(t!(wt.is_locked()), None);
+        t!(wt.lock(Some("on a removable drive")));
+        assert_eq!(t!(wt.is_locked()), Some("on a removable drive".to_string()));
+        let mut reason = String::new();
+        t!(t!(fs::File::open(td.path().join(".git/worktrees/wt/locked")))
+            .read_to_string(&mut reason));
+        assert_eq!(reason, "on a removable drive");
+        assert!(wt.lock(None).is_err());
+        assert!(t!(wt.unlock()));
+        assert_eq!(t!(wt.is_locked()), None);
+        assert!(!t!(wt.unlock()));
+
+        let wt = t!(repo.find_worktree("wt"));
+        let wtrepo = t!(Repository::open_from_worktree(&wt));
+        let head_id = t!(wtrepo.head()).target().unwrap();
+        t!(wtrepo.set_head_detached(head_id));
+        assert!(t!(repo.head_detached_for_worktree("wt")));
+        assert!(t!(repo.head()).is_branch());
+    }
+
+    #[test]
+    fn smoke_graph_reachable_from_any() {
+        let (_td, repo) = graph_repo_init();
+        let tip = t!(repo.head()).target().unwrap();
+        let root = t!(t!(repo.find_commit(tip)).parent_id(0));
+
+        let sig = t!(repo.signature());
+        let tree = t!(t!(repo.find_commit(tip)).tree());
+        let orphan = t!(repo.commit(None, &sig, &sig, "orphan", &tree, &[]));
+
+        assert!(t!(repo.graph_reachable_from_any(root, &[tip])));
+        assert!(t!(repo.graph_reachable_from_any(root, &[orphan, tip])));
+        assert!(t!(repo.graph_reachable_from_any(tip, &[tip])));
+        assert!(!t!(repo.graph_reachable_from_any(orphan, &[tip])));
+        assert!(!t!(repo.graph_reachable_from_any(tip, &[root])));
+        assert!(!t!(repo.graph_reachable_from_any(root, &[])));
+    }
+
+    #[test]
+    fn smoke_reference_foreach() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.branch("one", &head, false));
+        t!(repo.branch("two", &head, false));
+        t!(repo.tag_lightweight("v1", head.as_object(), false));
+
+        let mut names = Vec::new();
+        t!(repo.reference_foreach(|r| {
+            names.push(r.name().unwrap().to_string());
+            true
+        }));
+        assert_eq!(names.len(), t!(repo.references()).count());
+        assert!(names.iter().any(|n| n == "refs/tags/v1"));
+
+        let mut visited = 0;
+        t!(repo.reference_foreach(|_| { visited += 1; false }));
+        assert_eq!(visited, 1);
+
+        t!(repo.pack_references());
+        assert!(repo.path().join("packed-refs").exists());
+        assert!(!repo.path().join("refs/heads/one").exists());
+        let mut count = 0;
+        t!(repo.reference_foreach(|_| { count += 1; true }));
+        assert_eq!(count, names.len());
+    }
+
+    #[test]
+    fn smoke_refresh_attributes_cache() {
+        let (td, repo) = ::test::repo_init();
+        let path = Path::new("foo.log");
+        assert!(!t!(repo.status_should_ignore(path)));
+
+        t!(t!(fs::File::create(td.path().join(".gitignore")))
+            .write_all(b"*.log\n"));
+        t!(repo.refresh_attributes_cache());
+        assert!(t!(repo.status_should_ignore(path)));
+
+        t!(t!(fs::File::create(td.path().join(".gitignore")))
+            .write_all(b"*.tmp\n"));
+        t!(repo.refresh_attributes_cache());
+        assert!(!t!(repo.status_should_ignore(path)));
+    }
+
+    #[test]
+    fn smoke_resolve_refs() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head()).target().unwrap();
+        let results = repo.resolve_refs(&["refs/heads/master",
+                                          "refs/heads/missing",
+                                          "HEAD"]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(*results[0].as_ref().unwrap(), head);
+        assert_eq!(results[1].as_ref().unwrap_err().code(), ErrorCode::NotFound);
+        assert_eq!(*results[2].as_ref().unwrap(), head);
+        assert!(repo.resolve_refs(&[]).is_empty());
+    }
+
+    #[test]
+    fn smoke_is_worktree_and_commondir() {
+        let (_td, repo) = ::test::repo_init();
+        assert!(!repo.is_worktree());
+        assert_eq!(repo.commondir(), repo.path());
+
+        let wtdir = TempDir::new("worktree").unwrap();
+        let wt = t!(repo.worktree("wt", &wtdir.path().join("wt")));
+        let wtrepo = t!(Repository::open_from_worktree(&wt));
+        assert!(wtrepo.is_worktree());
+        assert!(wtrepo.commondir() != wtrepo.path());
+        assert_eq!(t!(wtrepo.commondir().canonicalize()),
+                   t!(repo.path().canonicalize()));
+        assert_eq!(t!(wtrepo.path().canonicalize()),
+                   t!(repo.path().join("worktrees/wt").canonicalize()));
+    }
+
+    #[test]
+    fn smoke_mergehead_foreach() {
+        let (_td, repo) = ::test::repo_init();
+        assert_eq!(repo.mergehead_foreach(|_| true).unwrap_err().code(),
+                   ErrorCode::NotFound);
+
+        let head = t!(repo.head()).target().unwrap();
+        t!(t!(fs::File::create(repo.path().join("MERGE_HEAD")))
+            .write_all(format!("{}\n", head).as_bytes()));
+        let mut ids = Vec::new();
+        t!(repo.mergehead_foreach(|id| { ids.push(id); true }));
+        assert_eq!(ids, [head]);
+    }
+
+    #[test]
+    fn smoke_fetchhead_foreach() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(repo.head()).target().unwrap();
+        let contents = format!("{id}\t\tbranch 'master' of {url}\n\
+                                {id}\tnot-for-merge\tbranch 'next' of {url}\n",
+                               id = head, url = "https://example.com/a");
+        t!(t!(fs::File::create(repo.path().join("FETCH_HEAD")))
+            .write_all(contents.as_bytes()));
+
+        let mut entries = Vec::new();
+        t!(repo.fetchhead_foreach(|name, url, id, is_merge| {
+            entries.push((name.to_vec(), url.to_vec(), id, is_merge));
+            true
+        }));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, b"refs/heads/master");
+        assert_eq!(entries[0].1, b"https://example.com/a");
+        assert_eq!(entries[0].2, head);
+        assert!(entries[0].3);
+        assert_eq!(entries[1].0, b"refs/heads/next");
+        assert!(!entries[1].3);
+
+        let mut visited = 0;
+        t!(repo.fetchhead_foreach(|_, _, _, _| { visited += 1; false }));
+        assert_eq!(visited, 1);
+
+        let mut contents = format!("{}\t\tbranch 'ma", head).into_bytes();
+        contents.extend_from_slice(b"\xffster' of /tmp/a\n");
+        t!(t!(fs::File::create(repo.path().join("FETCH_HEAD")))
+            .write_all(&contents));
+        let mut names = Vec::new();
+        t!(repo.fetchhead_foreach(|name, _, _, _| {
+            names.push(name.to_vec());
+            true
+        }));
+        assert_eq!(names, [b"refs/heads/ma\xffster".to_vec()]);
+    }
+
+    #[test]
+    fn smoke_remotes_config() {
+        let (_td, repo) = ::test::repo_init();
+        t!(repo.remote("origin", "https://example.com/origin"));
+        t!(repo.remote("up.stream", "https://example.com/upstream"));
+        t!(t!(repo.config()).set_str("remote.pushurl", "https://example.com"));
+
+        let mut remotes = t!(repo.remotes_config());
+        remotes.sort();
+        assert_eq!(remotes, [
+            ("origin".to_string(), "https://example.com/origin".to_string()),
+            ("up.stream".to_string(),
+             "https://example.com/upstream".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn smoke_file_history() {
+        let (td, repo) = ::test::repo_init();
+        let sig = repo.signature().unwrap();
+        let mut index = repo.index().unwrap();
+        let mut commit = |files: &[(&str, &str)], removed: &[&str]| {
+            for &(name, content) in files {
+                let mut f = fs::File::create(td.path().join(name)).unwrap();
+                f.write_all(content.as_bytes()).unwrap();
+                index.add_path(Path::new(name)).unwrap();
+            }
+            for name in removed {
+                fs::remove_file(td.path().join(name)).unwrap();
+                index.remove_path(Path::new(name)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let head = repo.head().unwrap().target().unwrap();
+            let parent = repo.find_commit(head).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "change", &tree,
+                        &[&parent]).unwrap()
+        };
+
+        let content = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+        let first = commit(&[("a", content), ("b", "b\n")], &[]);
+        commit(&[("b", "changed\n")], &[]);
+        let third = commit(&[("a", &format!("{}eight\n", content))], &[]);
+        let moved = commit(&[("c", &format!("{}eight\n", content))], &["a"]);
+
+        let history = repo.file_history(Path::new("a"), Some(third)).unwrap();
+        assert_eq!(history, [third, first]);
+
+        let history = repo.file_history(Path::new("c"), None).unwrap();
+        assert_eq!(history, [moved, third, first]);
+
+        let history = repo.file_history(Path::new("missing"), None).unwrap();
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn smoke_config_for_level() {
+        let (td, repo) = ::test::repo_init();
+        {
+            let mut local = repo.config_for_level(ConfigLevel::Local).unwrap();
+            local.set_str("test.scope", "local").unwrap();
+        }
+
+        let mut contents = String::new();
+        fs::File::open(td.path().join(".git/config")).unwrap()
+            .read_to_string(&mut contents).unwrap();
+        assert!(contents.contains("scope = local"));
+
+        let snapshot = repo.config_snapshot().unwrap();
+        assert_eq!(snapshot.get_str("test.scope").unwrap(), "local");
+
+        match repo.config_for_level(ConfigLevel::Global) {
+            Ok(mut global) => {
+                let global = global.snapshot().unwrap();
+                assert!(global.get_str("test.scope").is_err());
+            }
+            Err(e) => assert_eq!(e.code(), ErrorCode::NotFound),
+        }
+    }
+
+    #[test]
+    fn smoke_tree_stats() {
+        let (_td, repo) = ::test::repo_init();
+        let head = repo.head().unwrap().target().unwrap();
+        let a = repo.blob(b"foo").unwrap();
+        let b = repo.blob(b"hello").unwrap();
+
+        let mut nested = repo.treebuilder(None).unwrap();
+        nested.insert("b", b, 0o100644).unwrap();
+        let nested = nested.write().unwrap();
+
+        let mut root = repo.treebuilder(None).unwrap();
+        root.insert("a", a, 0o100644).unwrap();
+        root.insert("dir", nested, 0o040000).unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+        let stats = repo.tree_stats(&tree).unwrap();
+        assert_eq!(stats.blob_count, 2);
+        assert_eq!(stats.tree_count, 1);
+        assert_eq!(stats.submodule_count, 0);
+        assert_eq!(stats.total_size, 8);
+
+        root.insert("sub", head, 0o160000).unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+        let stats = repo.tree_stats(&tree).unwrap();
+        assert_eq!(stats.blob_count, 2);
+        assert_eq!(stats.submodule_count, 1);
+        assert_eq!(stats.total_size, 8);
+    }
+
+    #[test]
+    fn smoke_prepared_message() {
+        let (td, repo) = ::test::repo_init();
+        assert_eq!(repo.message().unwrap_err().code(), ErrorCode::NotFound);
+
+        repo.set_prepared_message("prepared\n\nbody\n").unwrap();
+        assert_eq!(repo.message().unwrap(), "prepared\n\nbody\n");
+        assert!(td.path().join(".git/MERGE_MSG").exists());
+        repo.set_prepared_message("replaced\n").unwrap();
+        assert_eq!(repo.message().unwrap(), "replaced\n");
+
+        let wtdir = TempDir::new("worktree").unwrap();
+        let wt = repo.worktree("wt", &wtdir.path().join("wt")).unwrap();
+        let wtrepo = Repository::open_from_worktree(&wt).unwrap();
+        wtrepo.set_prepared_message("in worktree\n").unwrap();
+        assert_eq!(wtrepo.message().unwrap(), "in worktree\n");
+        assert_eq!(repo.message().unwrap(), "replaced\n");
+
+        repo.remove_message().unwrap();
+        assert!(repo.message().is_err());
+        assert_eq!(wtrepo.message().unwrap(), "in worktree\n");
     }
 }
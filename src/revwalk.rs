@@ -1,24 +1,38 @@
 use std::marker;
 use std::ffi::CString;
-use libc::c_uint;
+use std::ptr;
+use libc::{c_int, c_uint, c_void};
 
-use {raw, Error, Sort, Oid, Repository};
+use {raw, panic, Error, Sort, Oid, Repository};
 use util::Binding;
 
 /// A revwalk allows traversal of the commit graph defined by including one or
 /// more leaves and excluding one or more roots.
 pub struct Revwalk<'repo> {
     raw: *mut raw::git_revwalk,
+    hide: Option<Box<Box<HideCb<'repo>>>>,
     _marker: marker::PhantomData<&'repo Repository>,
 }
 
+/// Callback used by `Revwalk::set_hide_callback`.
+///
+/// Returning `true` hides the commit and its ancestors from the walk.
+pub type HideCb<'a> = FnMut(Oid) -> bool + 'a;
+
 impl<'repo> Revwalk<'repo> {
     /// Reset a revwalk to allow re-configuring it.
     ///
     /// The revwalk is automatically reset when iteration of its commits
-    /// completes.
+    /// completes. Unlike that automatic reset, this also removes any callback
+    /// registered with `set_hide_callback`.
     pub fn reset(&mut self) {
-        unsafe { raw::git_revwalk_reset(self.raw()) }
+        unsafe {
+            raw::git_revwalk_reset(self.raw());
+            if self.hide.is_some() {
+                raw::git_revwalk_add_hide_cb(self.raw, None, ptr::null_mut());
+            }
+        }
+        self.hide = None;
     }
 
     /// Set the order in which commits are visited.
@@ -35,6 +49,26 @@ impl<'repo> Revwalk<'repo> {
         unsafe { raw::git_revwalk_simplify_first_parent(self.raw) }
     }
 
+    /// Register a callback deciding which commits to hide from the walk.
+    ///
+    /// The callback is invoked with each commit the walk reaches; returning
+    /// `true` hides that commit and its ancestors, just like `hide`. It
+    /// replaces any previously registered callback and stays registered
+    /// until `reset` is called or the revwalk is dropped.
+    pub fn set_hide_callback<F>(&mut self, cb: F) -> Result<(), Error>
+        where F: FnMut(Oid) -> bool + 'repo
+    {
+        let mut hide = Box::new(Box::new(cb) as Box<HideCb<'repo>>);
+        unsafe {
+            let f: raw::git_revwalk_hide_cb = hide_cb;
+            let payload = &mut *hide as *mut Box<HideCb> as *mut c_void;
+            try_call!(raw::git_revwalk_add_hide_cb(self.raw, Some(f),
+                                                   payload));
+        }
+        self.hide = Some(hide);
+        Ok(())
+    }
+
     /// Mark a commit to start traversal from.
     ///
     /// The given OID must belong to a committish on the walked repository.
@@ -147,12 +181,26 @@ impl<'repo> Revwalk<'repo> {
         }
         Ok(())
     }
+
+    /// Consume this revwalk, counting the commits it yields without
+    /// collecting them.
+    ///
+    /// Unlike `Iterator::count`, an error encountered during the walk is
+    /// returned rather than being counted as an item.
+    pub fn count(self) -> Result<usize, Error> {
+        let mut n = 0;
+        for oid in self {
+            try!(oid);
+            n += 1;
+        }
+        Ok(n)
+    }
 }
 
 impl<'repo> Binding for Revwalk<'repo> {
     type Raw = *mut raw::git_revwalk;
     unsafe fn from_raw(raw: *mut raw::git_revwalk) -> Revwalk<'repo> {
-        Revwalk { raw: raw, _marker: marker::PhantomData }
+        Revwalk { raw: raw, hide: None, _marker: marker::PhantomData }
     }
     fn raw(&self) -> *mut raw::git_revwalk { self.raw }
 }
@@ -174,6 +222,13 @@ impl<'repo> Iterator for Revwalk<'repo> {
     }
 }
 
+extern fn hide_cb(id: *const raw::git_oid, payload: *mut c_void) -> c_int {
+    panic::wrap(|| unsafe {
+        let hide = &mut *(payload as *mut Box<HideCb>);
+        if hide(Binding::from_raw(id)) { 1 } else { 0 }
+    }).unwrap_or(-1)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -200,4 +255,93 @@ mod tests {
         walk.hide_head().unwrap();
         assert_eq!(walk.by_ref().count(), 0);
     }
+
+    #[test]
+    fn count_range() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let base = t!(repo.head()).target().unwrap();
+        let tree = t!(t!(repo.find_commit(base)).tree());
+        let mut parent = base;
+        for i in 0..5 {
+            let commit = t!(repo.find_commit(parent));
+            parent = t!(repo.commit(Some("HEAD"), &sig, &sig,
+                                    &format!("commit {}", i), &tree, &[&commit]));
+        }
+
+        let mut walk = t!(repo.revwalk());
+        t!(walk.push(parent));
+        t!(walk.hide(base));
+        assert_eq!(t!(walk.count()), 5);
+        assert_eq!(t!(repo.commits_between(parent, base)), 5);
+        assert_eq!(t!(repo.commits_between(base, parent)), 0);
+    }
+
+    #[test]
+    fn simplify_first_parent() {
+        let (_td, repo) = ::test::repo_init();
+        let sig = t!(repo.signature());
+        let root = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(root.tree());
+        let commit = |msg: &str, parents: &[&::Commit]| {
+            let id = t!(repo.commit(None, &sig, &sig, msg, &tree, parents));
+            t!(repo.find_commit(id))
+        };
+        let main = commit("main", &[&root]);
+        let side = commit("side", &[&root]);
+        let merge = commit("merge", &[&main, &side]);
+        t!(repo.reference("refs/heads/merged", merge.id(), false, "merged"));
+        t!(repo.reference("refs/heads/side", side.id(), false, "side"));
+
+        let mut walk = t!(repo.revwalk());
+        walk.set_sorting(::SORT_TOPOLOGICAL);
+        t!(walk.push_ref("refs/heads/merged"));
+        let all = t!(walk.by_ref().collect::<Result<Vec<_>, _>>());
+        assert_eq!(all.len(), 4);
+        assert!(all.contains(&side.id()));
+
+        walk.reset();
+        walk.set_sorting(::SORT_TOPOLOGICAL);
+        walk.simplify_first_parent();
+        t!(walk.push(merge.id()));
+        let mainline = t!(walk.by_ref().collect::<Result<Vec<_>, _>>());
+        assert_eq!(mainline, vec![merge.id(), main.id(), root.id()]);
+
+        walk.reset();
+        walk.set_sorting(::SORT_TOPOLOGICAL | ::SORT_REVERSE);
+        t!(walk.push(merge.id()));
+        t!(walk.hide_ref("refs/heads/side"));
+        let ids = t!(walk.by_ref().collect::<Result<Vec<_>, _>>());
+        assert_eq!(ids, vec![main.id(), merge.id()]);
+    }
+
+    #[test]
+    fn hide_callback() {
+        let (_td, repo) = ::test::repo_init();
+        let root = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(root.tree());
+        let mut parent = root;
+        let mut ids = Vec::new();
+        for &time in &[1000, 2000, 3000, 4000] {
+            let sig = t!(::Signature::new("name", "email", &::Time::new(time, 0)));
+            let id = t!(repo.commit(None, &sig, &sig, "msg", &tree, &[&parent]));
+            ids.push(id);
+            parent = t!(repo.find_commit(id));
+        }
+
+        let mut walk = t!(repo.revwalk());
+        t!(walk.push(parent.id()));
+        t!(walk.set_hide_callback(|id| {
+            t!(repo.find_commit(id)).time().seconds() < 2500
+        }));
+        let kept = t!(walk.by_ref().collect::<Result<Vec<_>, _>>());
+        assert_eq!(kept, vec![ids[3], ids[2]]);
+
+        t!(walk.push(parent.id()));
+        assert_eq!(t!(walk.by_ref().collect::<Result<Vec<_>, _>>()), kept);
+
+        walk.reset();
+        t!(walk.push(parent.id()));
+        assert_eq!(t!(walk.count()), 5);
+    }
 }
@@ -59,6 +59,20 @@ impl<'a> Signature<'a> {
         }
     }
 
+    /// Parse a signature from its raw git representation, e.g.
+    /// `Name <email> 1234567890 +0000`.
+    ///
+    /// Returns an error if the buffer is not a well-formed signature.
+    pub fn from_buffer(buf: &[u8]) -> Result<Signature<'static>, Error> {
+        ::init();
+        let mut ret = ptr::null_mut();
+        let buf = try!(CString::new(buf));
+        unsafe {
+            try_call!(raw::git_signature_from_buffer(&mut ret, buf));
+            Ok(Binding::from_raw(ret))
+        }
+    }
+
     /// Gets the name on the signature.
     ///
     /// Returns `None` if the name is not valid utf-8
@@ -172,4 +186,30 @@ mod tests {
         drop(s.clone());
         drop(s.to_owned());
     }
+
+    #[test]
+    fn from_buffer() {
+        let s = Signature::now("foo", "bar@example.com").unwrap();
+        let when = s.when();
+        let offset = when.offset_minutes();
+        let buf = format!("{} <{}> {} {}{:02}{:02}",
+                          s.name().unwrap(), s.email().unwrap(),
+                          when.seconds(),
+                          if offset < 0 {'-'} else {'+'},
+                          offset.abs() / 60, offset.abs() % 60);
+
+        let parsed = Signature::from_buffer(buf.as_bytes()).unwrap();
+        assert_eq!(parsed.name(), s.name());
+        assert_eq!(parsed.email(), s.email());
+        assert_eq!(parsed.when().seconds(), when.seconds());
+        assert_eq!(parsed.when().offset_minutes(), offset);
+
+        let parsed = Signature::from_buffer(b"foo <bar> 1234567890 -0130")
+                               .unwrap();
+        assert_eq!(parsed.when().seconds(), 1234567890);
+        assert_eq!(parsed.when().offset_minutes(), -90);
+
+        assert!(Signature::from_buffer(b"foo bar 1234567890 +0000").is_err());
+        assert!(Signature::from_buffer(b"").is_err());
+    }
 }
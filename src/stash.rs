@@ -45,7 +45,21 @@ impl<'cb> StashApplyOptions<'cb> {
 
     /// Set stash application flag to GIT_STASH_APPLY_REINSTATE_INDEX
     pub fn reinstantiate_index(&mut self) -> &mut StashApplyOptions<'cb> {
-        self.raw_opts.flags = raw::GIT_STASH_APPLY_REINSTATE_INDEX;
+        self.reinstate_index(true)
+    }
+
+    /// Try to reinstate not only the working tree's changes, but also the
+    /// changes which were staged in the index when the stash was saved.
+    ///
+    /// If the stashed index changes conflict with the current index the
+    /// application fails with `ErrorCode::MergeConflict`.
+    pub fn reinstate_index(&mut self, reinstate: bool)
+                           -> &mut StashApplyOptions<'cb> {
+        if reinstate {
+            self.raw_opts.flags |= raw::GIT_STASH_APPLY_REINSTATE_INDEX;
+        } else {
+            self.raw_opts.flags &= !raw::GIT_STASH_APPLY_REINSTATE_INDEX;
+        }
         self
     }
 
@@ -64,7 +78,6 @@ impl<'cb> StashApplyOptions<'cb> {
     {
         self.progress = Some(Box::new(callback) as Box<StashApplyProgressCb<'cb>>);
         self.raw_opts.progress_cb = stash_apply_progress_cb;
-        self.raw_opts.progress_payload = self as *mut _ as *mut _;
         self
     }
 
@@ -75,6 +88,9 @@ impl<'cb> StashApplyOptions<'cb> {
                 opts.configure(&mut self.raw_opts.checkout_options);
             }
         }
+        if self.progress.is_some() {
+            self.raw_opts.progress_payload = self as *mut _ as *mut _;
+        }
         &self.raw_opts
     }
 }
@@ -142,7 +158,8 @@ mod tests {
     use std::fs;
     use std::path::Path;
     use test::{repo_init};
-    use {Repository, STATUS_WT_NEW, STASH_INCLUDE_UNTRACKED};
+    use {Repository, StashApplyProgress, STATUS_WT_NEW, STATUS_INDEX_NEW};
+    use {STASH_INCLUDE_UNTRACKED};
 
     fn make_stash<C>(next: C) where C: FnOnce(&mut Repository) {
         let (_td, mut repo) = repo_init();
@@ -207,4 +224,32 @@ mod tests {
             assert!(count_stash(repo) == 1)
         })
     }
+
+    #[test]
+    fn smoke_stash_pop_reinstate_index() {
+        let (td, mut repo) = repo_init();
+        let signature = repo.signature().unwrap();
+        fs::File::create(td.path().join("staged.txt")).unwrap()
+            .write("staged".as_bytes()).unwrap();
+        let rel_p = Path::new("staged.txt");
+        let mut index = repo.index().unwrap();
+        index.add_path(rel_p).unwrap();
+        index.write().unwrap();
+        assert!(repo.status_file(&rel_p).unwrap() == STATUS_INDEX_NEW);
+
+        repo.stash_save(&signature, "staged", None).unwrap();
+        assert!(repo.status_file(&rel_p).is_err());
+
+        let mut progress = Vec::new();
+        {
+            let mut options = StashApplyOptions::new();
+            options.reinstate_index(true);
+            options.progress_cb(|p| { progress.push(p); true });
+            repo.stash_pop(0, Some(&mut options)).unwrap();
+        }
+        assert!(count_stash(&mut repo) == 0);
+        assert!(repo.status_file(&rel_p).unwrap() == STATUS_INDEX_NEW);
+        assert_eq!(progress.first(), Some(&StashApplyProgress::LoadingStash));
+        assert_eq!(progress.last(), Some(&StashApplyProgress::Done));
+    }
 }
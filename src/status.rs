@@ -351,10 +351,10 @@ impl<'statuses> Binding for StatusEntry<'statuses> {
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::path::Path;
     use std::io::prelude::*;
-    use super::StatusOptions;
+    use super::{StatusOptions, StatusShow};
 
     #[test]
     fn smoke() {
@@ -404,4 +404,61 @@ mod tests {
         let status = t!(repo.status_file(Path::new("foo")));
         assert!(status.contains(::STATUS_WT_NEW));
     }
+
+    #[test]
+    fn show_index_only() {
+        let (td, repo) = ::test::repo_init();
+        t!(t!(File::create(td.path().join("staged"))).write_all(b"staged\n"));
+        t!(t!(File::create(td.path().join("unstaged"))).write_all(b"wt\n"));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("staged")));
+        t!(index.write());
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true).show(StatusShow::Index);
+        let statuses = t!(repo.statuses(Some(&mut opts)));
+        assert_eq!(statuses.len(), 1);
+        let status = statuses.get(0).unwrap();
+        assert_eq!(status.path(), Some("staged"));
+        assert_eq!(status.status(), ::STATUS_INDEX_NEW);
+
+        opts.show(StatusShow::Workdir);
+        let statuses = t!(repo.statuses(Some(&mut opts)));
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses.get(0).unwrap().path(), Some("unstaged"));
+
+        opts.show(StatusShow::IndexAndWorkdir);
+        assert_eq!(t!(repo.statuses(Some(&mut opts))).len(), 2);
+    }
+
+    #[test]
+    fn staged_rename() {
+        let (td, repo) = ::test::repo_init();
+        let contents = b"some contents which are long enough to compare\n";
+        t!(t!(File::create(td.path().join("old"))).write_all(contents));
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("old")));
+        let tree = t!(repo.find_tree(t!(index.write_tree())));
+        let sig = t!(repo.signature());
+        let head = t!(t!(repo.head()).peel_to_commit());
+        t!(repo.commit(Some("HEAD"), &sig, &sig, "add old", &tree, &[&head]));
+
+        t!(fs::rename(td.path().join("old"), td.path().join("new")));
+        t!(index.remove_path(Path::new("old")));
+        t!(index.add_path(Path::new("new")));
+        t!(index.write());
+
+        let mut opts = StatusOptions::new();
+        opts.renames_head_to_index(true);
+        let statuses = t!(repo.statuses(Some(&mut opts)));
+        assert_eq!(statuses.len(), 1);
+        let status = statuses.get(0).unwrap();
+        assert_eq!(status.status(), ::STATUS_INDEX_RENAMED);
+        let delta = status.head_to_index().unwrap();
+        assert_eq!(delta.old_file().path(), Some(Path::new("old")));
+        assert_eq!(delta.new_file().path(), Some(Path::new("new")));
+
+        let statuses = t!(repo.statuses(None));
+        assert_eq!(statuses.len(), 2);
+    }
 }
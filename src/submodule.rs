@@ -264,10 +264,11 @@ impl<'cb> Default for SubmoduleUpdateOptions<'cb> {
 mod tests {
     use std::path::Path;
     use std::fs;
+    use std::io::prelude::*;
     use tempdir::TempDir;
     use url::Url;
 
-    use Repository;
+    use {Repository, SubmoduleIgnore};
     use SubmoduleUpdateOptions;
 
     #[test]
@@ -314,6 +315,70 @@ mod tests {
         t!(s.add_finalize());
     }
 
+    #[test]
+    fn add_setup_and_finalize() {
+        let (_td, repo1) = ::test::repo_init();
+        let (td, repo2) = ::test::repo_init();
+
+        let url = Url::from_file_path(&repo1.workdir().unwrap()).unwrap();
+        let mut s = repo2.submodule(&url.to_string(), Path::new("bar"),
+                                    true).unwrap();
+        let mut gitmodules = String::new();
+        t!(t!(fs::File::open(td.path().join(".gitmodules")))
+            .read_to_string(&mut gitmodules));
+        assert!(gitmodules.contains("[submodule \"bar\"]"));
+        assert!(gitmodules.contains(&format!("url = {}", url)));
+
+        t!(fs::remove_dir_all(td.path().join("bar")));
+        t!(Repository::clone(&url.to_string(), td.path().join("bar")));
+        t!(s.add_finalize());
+
+        let index = t!(repo2.index());
+        assert!(index.get_path(Path::new(".gitmodules"), 0).is_some());
+        let entry = index.get_path(Path::new("bar"), 0).unwrap();
+        assert_eq!(entry.mode, 0o160000);
+        assert_eq!(entry.id, t!(repo1.head()).target().unwrap());
+
+        let submodules = t!(repo2.submodules());
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].name(), Some("bar"));
+        assert_eq!(submodules[0].url(), Some(&url.to_string()[..]));
+    }
+
+    #[test]
+    fn status_uninitialized() {
+        let (_td, repo1) = ::test::repo_init();
+        let (td, repo2) = ::test::repo_init();
+
+        let url = Url::from_file_path(&repo1.workdir().unwrap()).unwrap();
+        let mut s = repo2.submodule(&url.to_string(), Path::new("bar"),
+                                    true).unwrap();
+        t!(fs::remove_dir_all(td.path().join("bar")));
+        t!(Repository::clone(&url.to_string(), td.path().join("bar")));
+        t!(s.add_to_index(false));
+        t!(s.add_finalize());
+        let status = t!(repo2.submodule_status("bar", SubmoduleIgnore::None));
+        assert!(status.contains(::SUBMODULE_STATUS_IN_WD));
+        assert!(status.contains(::SUBMODULE_STATUS_INDEX_ADDED));
+        assert!(!status.contains(::SUBMODULE_STATUS_WD_UNINITIALIZED));
+
+        let sig = t!(repo2.signature());
+        let tree = t!(repo2.find_tree(t!(t!(repo2.index()).write_tree())));
+        let parent = t!(t!(repo2.head()).peel_to_commit());
+        t!(repo2.commit(Some("HEAD"), &sig, &sig, "add bar", &tree,
+                        &[&parent]));
+
+        let td3 = TempDir::new("test").unwrap();
+        let url2 = Url::from_file_path(td.path()).unwrap();
+        let repo3 = t!(Repository::clone(&url2.to_string(), td3.path()));
+        let status = t!(repo3.submodule_status("bar", SubmoduleIgnore::None));
+        assert!(status.contains(::SUBMODULE_STATUS_IN_HEAD |
+                                ::SUBMODULE_STATUS_IN_INDEX |
+                                ::SUBMODULE_STATUS_IN_CONFIG |
+                                ::SUBMODULE_STATUS_WD_UNINITIALIZED));
+        assert!(!status.contains(::SUBMODULE_STATUS_WD_MODIFIED));
+    }
+
     #[test]
     fn update_submodule() {
         // -----------------------------------
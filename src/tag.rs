@@ -3,11 +3,24 @@ use std::mem;
 use std::ptr;
 use std::str;
 
-use {raw, signature, Error, Oid, Object, Signature, ObjectType};
+use {raw, signature, Commit, Error, Oid, Object, Signature, ObjectType, Tree};
 use util::Binding;
 
 /// A structure to represent a git [tag][1]
 ///
+/// A tag borrows the `Repository` it was found in, like every other object
+/// handed out by a repository, so it can't outlive it:
+///
+/// ```compile_fail
+/// # use git2::Repository;
+/// let tag = {
+///     let repo = Repository::open(".").unwrap();
+///     let id = repo.refname_to_id("refs/tags/v1.0").unwrap();
+///     repo.find_tag(id).unwrap()
+/// };
+/// println!("{:?}", tag.name());
+/// ```
+///
 /// [1]: http://git-scm.com/book/en/Git-Basics-Tagging
 pub struct Tag<'repo> {
     raw: *mut raw::git_tag,
@@ -57,7 +70,20 @@ impl<'repo> Tag<'repo> {
 
     /// Get the tagger (author) of a tag
     ///
-    /// If the author is unspecified, then `None` is returned.
+    /// If the author is unspecified, then `None` is returned. The signature
+    /// borrows from the tag; use `Signature::to_owned` to keep it around
+    /// after the tag has been dropped.
+    ///
+    /// ```compile_fail
+    /// # use git2::Repository;
+    /// let repo = Repository::open(".").unwrap();
+    /// let id = repo.refname_to_id("refs/tags/v1.0").unwrap();
+    /// let tagger = {
+    ///     let tag = repo.find_tag(id).unwrap();
+    ///     tag.tagger().unwrap()
+    /// };
+    /// println!("{:?}", tagger.name());
+    /// ```
     pub fn tagger(&self) -> Option<Signature> {
         unsafe {
             let ptr = raw::git_tag_tagger(&*self.raw);
@@ -81,6 +107,22 @@ impl<'repo> Tag<'repo> {
         }
     }
 
+    /// Get the tagged object of a tag as a commit.
+    ///
+    /// An error is returned if the tag does not point directly at a commit.
+    pub fn target_commit(&self) -> Result<Commit<'repo>, Error> {
+        let obj = try!(self.target());
+        obj.into_commit().map_err(|obj| mismatch(&obj, ObjectType::Commit))
+    }
+
+    /// Get the tagged object of a tag as a tree.
+    ///
+    /// An error is returned if the tag does not point directly at a tree.
+    pub fn target_tree(&self) -> Result<Tree<'repo>, Error> {
+        let obj = try!(self.target());
+        obj.into_tree().map_err(|obj| mismatch(&obj, ObjectType::Tree))
+    }
+
     /// Get the OID of the tagged object of a tag
     pub fn target_id(&self) -> Oid {
         unsafe { Binding::from_raw(raw::git_tag_target_id(&*self.raw)) }
@@ -107,6 +149,12 @@ impl<'repo> Tag<'repo> {
     }
 }
 
+fn mismatch(obj: &Object, expected: ObjectType) -> Error {
+    let found = obj.kind().map(|k| k.str()).unwrap_or("unknown object");
+    Error::from_str(&format!("tag target is a {}, not a {}", found,
+                             expected.str()))
+}
+
 impl<'repo> ::std::fmt::Debug for Tag<'repo> {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
         let mut ds = f.debug_struct("Tag");
@@ -173,6 +221,46 @@ mod tests {
         repo.tag_delete("foo").unwrap();
     }
 
+    #[test]
+    fn find_by_name() {
+        let (_td, repo) = ::test::repo_init();
+        let id = t!(repo.head()).target().unwrap();
+        let obj = t!(repo.find_object(id, None));
+        let sig = t!(repo.signature());
+        let tag_id = t!(repo.tag("foo", &obj, &sig, "msg", false));
+        t!(repo.tag_lightweight("lite", &obj, false));
+
+        let tag = t!(repo.find_tag_by_name("foo"));
+        assert_eq!(tag.id(), tag_id);
+        assert_eq!(tag.name(), Some("foo"));
+
+        let err = repo.find_tag_by_name("lite").unwrap_err();
+        assert!(err.message().contains("lightweight"));
+        let err = repo.find_tag_by_name("missing").unwrap_err();
+        assert_eq!(err.code(), ::ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn target_commit_and_tree() {
+        let (_td, repo) = ::test::repo_init();
+        let head = t!(t!(repo.head()).peel_to_commit());
+        let tree = t!(head.tree());
+        let sig = t!(repo.signature());
+        let commit_tag = t!(repo.tag("commit", head.as_object(), &sig, "msg",
+                                     false));
+        let tree_tag = t!(repo.tag("tree", tree.as_object(), &sig, "msg",
+                                   false));
+
+        let tag = t!(repo.find_tag(commit_tag));
+        assert_eq!(t!(tag.target_commit()).id(), head.id());
+        let err = tag.target_tree().unwrap_err();
+        assert_eq!(err.message(), "tag target is a commit, not a tree");
+
+        let tag = t!(repo.find_tag(tree_tag));
+        assert_eq!(t!(tag.target_tree()).id(), tree.id());
+        assert!(tag.target_commit().is_err());
+    }
+
     #[test]
     fn lite() {
         let (_td, repo) = ::test::repo_init();
@@ -188,4 +276,44 @@ mod tests {
         let tags = t!(repo.tag_names(Some("b*")));
         assert_eq!(tags.len(), 0);
     }
+
+    #[test]
+    fn names_glob() {
+        let (_td, repo) = ::test::repo_init();
+        let id = t!(repo.head()).target().unwrap();
+        let obj = t!(repo.find_object(id, None));
+        for name in &["v1.0", "v1.1", "v10", "v2.0"] {
+            t!(repo.tag_lightweight(name, &obj, false));
+        }
+
+        let tags = t!(repo.tag_names(Some("v1.*")));
+        let mut names = tags.iter().map(|s| s.unwrap()).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["v1.0", "v1.1"]);
+
+        let tags = t!(repo.tag_names_bytes(Some(b"v2.*")));
+        assert_eq!(tags.iter_bytes().collect::<Vec<_>>(), [&b"v2.0"[..]]);
+
+        assert_eq!(t!(repo.tag_names(Some("v3*"))).len(), 0);
+        assert_eq!(t!(repo.tag_names_bytes(None)).len(), 4);
+    }
+
+    #[test]
+    fn tagger_to_owned() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        let obj = repo.find_object(id, None).unwrap();
+        let sig = ::Signature::now("tagger", "tagger@example.com").unwrap();
+        let tag_id = repo.tag("owned", &obj, &sig, "msg", false).unwrap();
+
+        let tagger;
+        {
+            let tag = repo.find_tag(tag_id).unwrap();
+            tagger = tag.tagger().unwrap().to_owned();
+        }
+        assert_eq!(tagger.name(), Some("tagger"));
+        assert_eq!(tagger.email(), Some("tagger@example.com"));
+        assert!(tagger.when() == sig.when());
+        assert_eq!(tagger.clone().name(), Some("tagger"));
+    }
 }
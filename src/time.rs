@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::fmt;
 
 use libc::c_int;
 
@@ -33,6 +34,23 @@ impl Time {
 
     /// Return the timezone offset, in minutes
     pub fn offset_minutes(&self) -> i32 { self.raw.offset as i32 }
+
+    /// Return the timezone offset formatted the way git does, e.g. `+0200`
+    /// or `-0500`.
+    pub fn to_offset_string(&self) -> String {
+        let offset = self.offset_minutes();
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.abs();
+        format!("{}{:02}{:02}", sign, offset / 60, offset % 60)
+    }
+}
+
+/// Formats the time as git does in raw commit headers: the seconds since the
+/// epoch followed by the timezone offset, e.g. `1234567890 -0500`.
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.seconds(), self.to_offset_string())
+    }
 }
 
 impl PartialOrd for Time {
@@ -93,3 +111,22 @@ impl Ord for IndexTime {
         me.cmp(&other)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use Time;
+
+    #[test]
+    fn offset_string() {
+        let time = Time::new(1234567890, -300);
+        assert_eq!(time.seconds(), 1234567890);
+        assert_eq!(time.offset_minutes(), -300);
+        assert_eq!(time.to_offset_string(), "-0500");
+        assert_eq!(time.to_string(), "1234567890 -0500");
+
+        assert_eq!(Time::new(0, 120).to_offset_string(), "+0200");
+        assert_eq!(Time::new(0, 0).to_offset_string(), "+0000");
+        assert_eq!(Time::new(0, 330).to_offset_string(), "+0530");
+        assert_eq!(Time::new(0, -90).to_offset_string(), "-0130");
+    }
+}
@@ -0,0 +1,156 @@
+use std::ffi::CString;
+use std::marker;
+
+use {raw, Error, Oid, Repository, Signature};
+use util::Binding;
+
+/// A set of reference updates which are applied together, created by
+/// `Repository::transaction`.
+///
+/// Every reference to be changed must first be locked with `lock_ref`, which
+/// fails with `ErrorCode::Locked` if something else already holds the lock.
+/// Updates are only queued up until `commit` is called. Dropping the
+/// transaction without committing it releases the locks and leaves all of
+/// the references untouched.
+pub struct Transaction<'repo> {
+    raw: *mut raw::git_transaction,
+    _marker: marker::PhantomData<&'repo Repository>,
+}
+
+impl<'repo> Transaction<'repo> {
+    /// Lock the reference with the given name so that it can be updated.
+    ///
+    /// The reference doesn't need to exist yet.
+    pub fn lock_ref(&mut self, name: &str) -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_transaction_lock_ref(self.raw, name));
+        }
+        Ok(())
+    }
+
+    /// Queue an update of a locked reference to point directly at `target`.
+    ///
+    /// If no signature is given the repository's default one is used for
+    /// the reflog entry.
+    pub fn set_target(&mut self,
+                      name: &str,
+                      target: Oid,
+                      sig: Option<&Signature>,
+                      msg: &str) -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        let msg = try!(CString::new(msg));
+        unsafe {
+            try_call!(raw::git_transaction_set_target(self.raw, name,
+                                                      target.raw(),
+                                                      sig.map(|s| s.raw()),
+                                                      msg));
+        }
+        Ok(())
+    }
+
+    /// Queue an update of a locked reference to be a symbolic reference to
+    /// `target`.
+    ///
+    /// If no signature is given the repository's default one is used for
+    /// the reflog entry.
+    pub fn set_symbolic_target(&mut self,
+                               name: &str,
+                               target: &str,
+                               sig: Option<&Signature>,
+                               msg: &str) -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        let target = try!(CString::new(target));
+        let msg = try!(CString::new(msg));
+        unsafe {
+            try_call!(raw::git_transaction_set_symbolic_target(
+                self.raw, name, target, sig.map(|s| s.raw()), msg));
+        }
+        Ok(())
+    }
+
+    /// Queue the removal of a locked reference.
+    pub fn remove(&mut self, name: &str) -> Result<(), Error> {
+        let name = try!(CString::new(name));
+        unsafe {
+            try_call!(raw::git_transaction_remove(self.raw, name));
+        }
+        Ok(())
+    }
+
+    /// Write all of the queued updates to disk and release the locks.
+    pub fn commit(self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_transaction_commit(self.raw));
+        }
+        Ok(())
+    }
+}
+
+impl<'repo> Binding for Transaction<'repo> {
+    type Raw = *mut raw::git_transaction;
+    unsafe fn from_raw(raw: *mut raw::git_transaction) -> Transaction<'repo> {
+        Transaction {
+            raw: raw,
+            _marker: marker::PhantomData,
+        }
+    }
+    fn raw(&self) -> *mut raw::git_transaction { self.raw }
+}
+
+impl<'repo> Drop for Transaction<'repo> {
+    fn drop(&mut self) {
+        unsafe { raw::git_transaction_free(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ErrorCode;
+
+    #[test]
+    fn commit_updates_all_refs() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/heads/old", id, false, "old").unwrap();
+
+        let mut tx = repo.transaction().unwrap();
+        tx.lock_ref("refs/heads/one").unwrap();
+        tx.lock_ref("refs/heads/two").unwrap();
+        tx.lock_ref("refs/heads/old").unwrap();
+        tx.set_target("refs/heads/one", id, None, "one").unwrap();
+        tx.set_symbolic_target("refs/heads/two", "refs/heads/one", None,
+                               "two").unwrap();
+        tx.remove("refs/heads/old").unwrap();
+        assert!(repo.find_reference("refs/heads/one").is_err());
+        tx.commit().unwrap();
+
+        assert_eq!(repo.refname_to_id("refs/heads/one").unwrap(), id);
+        let two = repo.find_reference("refs/heads/two").unwrap();
+        assert_eq!(two.symbolic_target(), Some("refs/heads/one"));
+        assert!(repo.find_reference("refs/heads/old").is_err());
+    }
+
+    #[test]
+    fn failed_lock_applies_nothing() {
+        let (_td, repo) = ::test::repo_init();
+        let id = repo.head().unwrap().target().unwrap();
+
+        let mut holder = repo.transaction().unwrap();
+        holder.lock_ref("refs/heads/two").unwrap();
+
+        {
+            let mut tx = repo.transaction().unwrap();
+            tx.lock_ref("refs/heads/one").unwrap();
+            tx.set_target("refs/heads/one", id, None, "one").unwrap();
+            let err = tx.lock_ref("refs/heads/two").unwrap_err();
+            assert_eq!(err.code(), ErrorCode::Locked);
+        }
+        drop(holder);
+
+        assert!(repo.find_reference("refs/heads/one").is_err());
+        assert!(repo.find_reference("refs/heads/two").is_err());
+        let mut tx = repo.transaction().unwrap();
+        tx.lock_ref("refs/heads/two").unwrap();
+    }
+}
@@ -33,6 +33,20 @@ pub struct TreeIter<'tree> {
     tree: &'tree Tree<'tree>,
 }
 
+/// Totals over every entry reachable from a tree, computed by
+/// `Repository::tree_stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeStats {
+    /// Number of blobs, i.e. files and symbolic links.
+    pub blob_count: usize,
+    /// Number of subtrees, not counting the tree itself.
+    pub tree_count: usize,
+    /// Number of submodule (gitlink) entries.
+    pub submodule_count: usize,
+    /// Sum of the sizes of all blobs, in bytes.
+    pub total_size: u64,
+}
+
 impl<'repo> Tree<'repo> {
     /// Get the id (SHA1) of a repository object
     pub fn id(&self) -> Oid {
@@ -188,6 +202,9 @@ impl<'tree> TreeEntry<'tree> {
     }
 
     /// Convert a tree entry to the object it points to.
+    ///
+    /// For a submodule (gitlink) entry the commit is looked up in `repo`, so
+    /// an error is returned if that commit isn't present in it.
     pub fn to_object<'a>(&self, repo: &'a Repository)
                          -> Result<Object<'a>, Error> {
         let mut ret = ptr::null_mut();
@@ -204,6 +221,8 @@ impl<'tree> TreeEntry<'tree> {
     }
 
     /// Get the UNIX file attributes of a tree entry
+    ///
+    /// Use `FileMode::try_from` to convert the result to a `FileMode`.
     pub fn filemode(&self) -> i32 {
         unsafe { raw::git_tree_entry_filemode(&*self.raw) as i32 }
     }
@@ -403,4 +422,53 @@ mod tests {
         repo.find_object(commit.tree_id(), None).unwrap().as_tree().unwrap();
         repo.find_object(commit.tree_id(), None).unwrap().into_tree().ok().unwrap();
     }
+
+    #[test]
+    fn iter_sorted() {
+        let (td, repo) = ::test::repo_init();
+        let mut index = repo.index().unwrap();
+        File::create(&td.path().join("b")).unwrap().write_all(b"b").unwrap();
+        File::create(&td.path().join("a")).unwrap().write_all(b"a").unwrap();
+        index.add_path(Path::new("b")).unwrap();
+        index.add_path(Path::new("a")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        assert_eq!(tree.len(), 2);
+        let names = tree.iter().map(|e| e.name().unwrap().to_string())
+                        .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b"]);
+        let names = tree.iter().rev().map(|e| e.name_bytes().to_vec())
+                        .collect::<Vec<_>>();
+        assert_eq!(names, [b"b".to_vec(), b"a".to_vec()]);
+
+        let a = tree.get(0).unwrap();
+        assert_eq!(a.kind(), Some(ObjectType::Blob));
+        assert_eq!(a.filemode(), 0o100644);
+        assert!(tree.get(2).is_none());
+        assert!(tree.get_name("c").is_none());
+        assert_eq!(tree.get_id(a.id()).unwrap().name(), Some("a"));
+    }
+
+    #[test]
+    fn entry_to_object() {
+        let (_td, repo) = ::test::repo_init();
+        let blob_id = repo.blob(b"contents").unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert("file", blob_id, 0o100644).unwrap();
+        builder.insert("sub", head, 0o160000).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+        let entry = tree.get_name("file").unwrap();
+        let obj = entry.to_object(&repo).unwrap();
+        assert_eq!(obj.id(), blob_id);
+        let blob = obj.as_blob().unwrap();
+        assert_eq!(blob.content(), repo.find_blob(blob_id).unwrap().content());
+
+        let gitlink = tree.get_name("sub").unwrap();
+        assert_eq!(gitlink.kind(), Some(ObjectType::Commit));
+        let obj = gitlink.to_object(&repo).unwrap();
+        assert_eq!(obj.kind(), Some(ObjectType::Commit));
+        assert_eq!(obj.id(), head);
+    }
 }
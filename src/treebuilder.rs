@@ -49,11 +49,13 @@ impl<'repo> TreeBuilder<'repo> {
     /// an object of a reasonable type (or any object at all).
     ///
     /// The mode given must be one of 0o040000, 0o100644, 0o100755, 0o120000 or
-    /// 0o160000 currently.
-    pub fn insert<P: IntoCString>(&mut self, filename: P, oid: Oid,
-                                  filemode: i32) -> Result<TreeEntry, Error> {
+    /// 0o160000 currently, either as a raw integer or as a `FileMode`.
+    pub fn insert<P, M>(&mut self, filename: P, oid: Oid,
+                        filemode: M) -> Result<TreeEntry, Error>
+        where P: IntoCString, M: Into<i32>
+    {
         let filename = try!(filename.into_c_string());
-        let filemode = filemode as raw::git_filemode_t;
+        let filemode = filemode.into() as raw::git_filemode_t;
 
         let mut ret = ptr::null();
         unsafe {
@@ -132,7 +134,7 @@ impl<'repo> Drop for TreeBuilder<'repo> {
 
 #[cfg(test)]
 mod tests {
-    use ObjectType;
+    use {FileMode, ObjectType};
 
     #[test]
     fn smoke() {
@@ -196,4 +198,19 @@ mod tests {
         builder.filter(|_| false);
         assert_eq!(builder.len(), 0);
     }
+
+    #[test]
+    fn insert_file_mode() {
+        let (_td, repo) = ::test::repo_init();
+
+        let mut builder = repo.treebuilder(None).unwrap();
+        let blob = repo.blob(b"#!/bin/sh").unwrap();
+        builder.insert("script", blob, FileMode::BlobExecutable).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+
+        let entry = tree.get_name("script").unwrap();
+        assert_eq!(entry.filemode(), 0o100755);
+        assert_eq!(FileMode::try_from(entry.filemode()),
+                   Some(FileMode::BlobExecutable));
+    }
 }
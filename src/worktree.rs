@@ -0,0 +1,76 @@
+use std::str;
+
+use {raw, Buf, Error};
+use util::Binding;
+
+/// A linked working tree of a repository, as created by `git worktree add`.
+///
+/// Each worktree has its own HEAD and index while sharing the object
+/// database and references of the repository it was created from.
+pub struct Worktree {
+    raw: *mut raw::git_worktree,
+}
+
+impl Worktree {
+    /// Check that the worktree's administrative files and working directory
+    /// are still present and valid.
+    pub fn validate(&self) -> Result<(), Error> {
+        unsafe {
+            try_call!(raw::git_worktree_validate(self.raw));
+        }
+        Ok(())
+    }
+
+    /// Lock the worktree, preventing it from being pruned.
+    ///
+    /// The optional reason is recorded in the worktree's `locked` file in the
+    /// repository's `worktrees` directory. An error is returned if the
+    /// worktree is already locked.
+    pub fn lock(&self, reason: Option<&str>) -> Result<(), Error> {
+        let reason = try!(::opt_cstr(reason));
+        unsafe {
+            try_call!(raw::git_worktree_lock(self.raw, reason));
+        }
+        Ok(())
+    }
+
+    /// Unlock a locked worktree.
+    ///
+    /// Returns `false` if the worktree was not locked.
+    pub fn unlock(&self) -> Result<bool, Error> {
+        let rc = unsafe { try_call!(raw::git_worktree_unlock(self.raw)) };
+        Ok(rc == 0)
+    }
+
+    /// Check whether the worktree is locked.
+    ///
+    /// Returns the reason it was locked with, which is empty if no reason was
+    /// given, or `None` if it isn't locked.
+    pub fn is_locked(&self) -> Result<Option<String>, Error> {
+        let buf = Buf::new();
+        let rc = unsafe {
+            try_call!(raw::git_worktree_is_locked(buf.raw(), self.raw))
+        };
+        if rc == 0 {
+            return Ok(None)
+        }
+        match str::from_utf8(&buf) {
+            Ok(reason) => Ok(Some(reason.to_string())),
+            Err(..) => Err(Error::from_str("lock reason is not valid utf-8")),
+        }
+    }
+}
+
+impl Binding for Worktree {
+    type Raw = *mut raw::git_worktree;
+    unsafe fn from_raw(raw: *mut raw::git_worktree) -> Worktree {
+        Worktree { raw: raw }
+    }
+    fn raw(&self) -> *mut raw::git_worktree { self.raw }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        unsafe { raw::git_worktree_free(self.raw) }
+    }
+}
@@ -0,0 +1,72 @@
+//! Tests of libgit2's process-global options.
+//!
+//! Changing these options while another thread is using libgit2 is a data
+//! race, so they are tested here, in their own test binary, from a single
+//! `#[test]` which runs each check in turn.
+
+extern crate git2;
+extern crate tempdir;
+
+use std::fs::File;
+use std::io::prelude::*;
+
+use git2::{opts, Config, ConfigLevel, Repository, Signature};
+use tempdir::TempDir;
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {}", stringify!($e), e),
+    })
+}
+
+#[test]
+fn global_state() {
+    global_search_path();
+    cache_max_size();
+}
+
+fn global_search_path() {
+    let td = TempDir::new("test").unwrap();
+    t!(t!(File::create(td.path().join(".gitconfig")))
+        .write_all(b"[opts]\n\tsearchpath = isolated\n"));
+    unsafe {
+        let old = t!(opts::get_search_path(ConfigLevel::Global));
+        t!(opts::set_search_path(ConfigLevel::Global, td.path()));
+        let current = opts::get_search_path(ConfigLevel::Global);
+        let found = Config::find_global();
+        let cfg = Config::open_default();
+        t!(opts::set_search_path(ConfigLevel::Global, &old));
+
+        assert_eq!(t!(current), td.path());
+        assert_eq!(t!(found).file_name().unwrap(), ".gitconfig");
+        assert_eq!(t!(t!(cfg).get_string("opts.searchpath")), "isolated");
+    }
+}
+
+fn cache_max_size() {
+    let td = TempDir::new("test").unwrap();
+    let repo = t!(Repository::init(td.path()));
+    let sig = t!(Signature::now("name", "email"));
+    let tree = t!(repo.find_tree(t!(t!(repo.index()).write_tree())));
+    let id = t!(repo.commit(None, &sig, &sig, "msg", &tree, &[]));
+    unsafe {
+        let old = t!(opts::cached_memory()).1;
+        let set = opts::set_cache_max_size(0);
+        let found = repo.find_commit(id);
+        let memory = opts::cached_memory();
+        t!(opts::set_cache_max_size(old as isize));
+
+        t!(set);
+        let commit = t!(found);
+        let (current, allowed) = t!(memory);
+        assert_eq!(allowed, 0);
+        // The only thing cached beforehand is the empty tree, which has no
+        // content, so at most the commit just looked up remains in the cache.
+        let size = commit.raw_header_bytes().len() + 1 +
+                   commit.message_raw_bytes().len();
+        assert!(current <= size, "{} bytes cached, expected at most {}",
+                current, size);
+        assert_eq!(t!(opts::cached_memory()).1, old);
+    }
+}